@@ -0,0 +1,426 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use soroban_sdk::{Bytes, BytesN, Env};
+use soroban_token_contract::arbitrary::{ArbitraryBigInt, ArbitraryIdentifier, ArbitrarySignature};
+use soroban_token_contract::testutils::register_test_contract as register_token;
+use soroban_token_contract::{
+    Action, FreezeMode, MetaField, MintAction, Timelock, TokenClient, VelocityLimit,
+};
+use soroban_token_contract_fuzz::{call, call_unauthorized};
+
+#[derive(Arbitrary, Debug)]
+struct Auth {
+    signature: ArbitrarySignature,
+    nonce: ArbitraryBigInt,
+    valid_until: Option<u32>,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Call {
+    Initialize(ArbitraryIdentifier, u32, Vec<u8>, Vec<u8>),
+    InitWrap(ArbitraryIdentifier, [u8; 32], Vec<u8>, Vec<u8>),
+    Nonce(ArbitraryIdentifier, Option<u64>),
+    Allowance(ArbitraryIdentifier, ArbitraryIdentifier),
+    Approve(Auth, ArbitraryIdentifier, ArbitraryBigInt),
+    Balance(ArbitraryIdentifier),
+    IsFrozen(ArbitraryIdentifier),
+    Xfer(Auth, ArbitraryIdentifier, ArbitraryBigInt),
+    XferFrom(
+        Auth,
+        ArbitraryIdentifier,
+        ArbitraryIdentifier,
+        ArbitraryBigInt,
+    ),
+    Burn(Auth, ArbitraryIdentifier, ArbitraryBigInt),
+    Freeze(Auth, ArbitraryIdentifier, u8, u32, Option<Vec<u8>>),
+    Mint(Auth, ArbitraryIdentifier, ArbitraryBigInt),
+    SetAdmin(Auth, ArbitraryIdentifier),
+    Unfreeze(Auth, ArbitraryIdentifier, u32, Option<Vec<u8>>),
+    Decimals,
+    Name,
+    Symbol,
+    Delegate(Auth, ArbitraryIdentifier),
+    Votes(ArbitraryIdentifier),
+    VotesAt(ArbitraryIdentifier, u32),
+    XferRelay(
+        Auth,
+        ArbitraryIdentifier,
+        ArbitraryBigInt,
+        ArbitraryIdentifier,
+        ArbitraryBigInt,
+    ),
+    SetFee(
+        Auth,
+        u32,
+        ArbitraryBigInt,
+        ArbitraryBigInt,
+        ArbitraryIdentifier,
+    ),
+    SetExempt(Auth, ArbitraryIdentifier, bool),
+    QuoteXfer(ArbitraryIdentifier, ArbitraryIdentifier, ArbitraryBigInt),
+    SharesOf(ArbitraryIdentifier),
+    Index,
+    SetOracle(Auth, ArbitraryIdentifier),
+    Rebase(Auth, ArbitraryBigInt),
+    SetRate(Auth, ArbitraryBigInt),
+    Accrued(ArbitraryIdentifier),
+    Supply,
+    Deposit(Auth, ArbitraryBigInt),
+    Withdraw(Auth, ArbitraryBigInt),
+    SetMeta(Auth, Vec<u8>, Vec<u8>),
+    SetField(Auth, u8, Vec<u8>),
+    Metadata,
+    Version,
+    Migrate(Auth),
+    Invalidate(Auth, ArbitraryBigInt),
+    HolderCnt,
+    Holders(u32, u32),
+    Allowances(ArbitraryIdentifier, u32, u32),
+    RevokeAll(Auth),
+    FreezeRec(ArbitraryIdentifier),
+    FreezeLog(ArbitraryIdentifier),
+    SetComply(Auth, Option<[u8; 32]>),
+    CheckXfer(ArbitraryIdentifier, ArbitraryIdentifier, ArbitraryBigInt),
+    SetMax(Auth, Option<ArbitraryBigInt>),
+    SetMaxId(Auth, ArbitraryIdentifier, Option<ArbitraryBigInt>),
+    SetMaxEx(Auth, ArbitraryIdentifier, bool),
+    MaxBal(ArbitraryIdentifier),
+    SetLimit(Auth, ArbitraryIdentifier, Option<(ArbitraryBigInt, u64)>),
+    Remaining(ArbitraryIdentifier),
+    SetTlock(Auth, Option<(u64, ArbitraryBigInt)>),
+    Queue(Auth, QueueAction),
+    Execute(Auth, u32),
+    Cancel(Auth, u32),
+    Queued,
+}
+
+#[derive(Arbitrary, Debug)]
+enum QueueAction {
+    Mint(ArbitraryIdentifier, ArbitraryBigInt),
+    SetAdmin(ArbitraryIdentifier),
+    Rebase(ArbitraryBigInt),
+    Unlock,
+}
+
+fn meta_field(n: u8) -> MetaField {
+    match n % 4 {
+        0 => MetaField::IconUri,
+        1 => MetaField::HomeDomain,
+        2 => MetaField::Desc,
+        _ => MetaField::IssuerUrl,
+    }
+}
+
+fn freeze_mode(n: u8) -> FreezeMode {
+    match n % 4 {
+        0 => FreezeMode::Off,
+        1 => FreezeMode::Outbound,
+        2 => FreezeMode::Inbound,
+        _ => FreezeMode::Full,
+    }
+}
+
+fn apply(e: &Env, client: &TokenClient, c: &Call) {
+    let id = |a: &ArbitraryIdentifier| a.identifier(e);
+    let int = |a: &ArbitraryBigInt| a.bigint(e);
+    let bytes = |v: &Vec<u8>| Bytes::from_slice(e, v);
+    let sig = |a: &Auth| a.signature.signature(e);
+    let nonce = |a: &Auth| a.nonce.bigint(e);
+    let _ = match c {
+        Call::Initialize(admin, decimal, name, symbol) => {
+            call(|| client.initialize(&id(admin), decimal, &bytes(name), &bytes(symbol)))
+        }
+        Call::InitWrap(admin, underlying, name, symbol) => call_unauthorized(|| {
+            client.init_wrap(
+                &id(admin),
+                &BytesN::from_array(e, underlying),
+                &bytes(name),
+                &bytes(symbol),
+            )
+        }),
+        Call::Nonce(a, lane) => call(|| {
+            client.nonce(&id(a), lane);
+        }),
+        Call::Allowance(from, spender) => call(|| {
+            client.allowance(&id(from), &id(spender));
+        }),
+        Call::Approve(a, spender, amount) => call_unauthorized(|| {
+            client.approve(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &id(spender),
+                &int(amount),
+            )
+        }),
+        Call::Balance(a) => call(|| {
+            client.balance(&id(a));
+        }),
+        Call::IsFrozen(a) => call(|| {
+            client.is_frozen(&id(a));
+        }),
+        Call::Xfer(a, to, amount) => call_unauthorized(|| {
+            client.xfer(&sig(a), &nonce(a), &a.valid_until, &id(to), &int(amount))
+        }),
+        Call::XferFrom(a, from, to, amount) => call_unauthorized(|| {
+            client.xfer_from(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &id(from),
+                &id(to),
+                &int(amount),
+            )
+        }),
+        Call::Burn(a, from, amount) => call_unauthorized(|| {
+            client.burn(&sig(a), &nonce(a), &a.valid_until, &id(from), &int(amount))
+        }),
+        Call::Freeze(a, target, mode, reason, reference) => call_unauthorized(|| {
+            client.freeze(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &id(target),
+                &freeze_mode(*mode),
+                reason,
+                &reference.as_ref().map(bytes),
+            )
+        }),
+        Call::Mint(a, to, amount) => call_unauthorized(|| {
+            client.mint(&sig(a), &nonce(a), &a.valid_until, &id(to), &int(amount))
+        }),
+        Call::SetAdmin(a, new_admin) => call_unauthorized(|| {
+            client.set_admin(&sig(a), &nonce(a), &a.valid_until, &id(new_admin))
+        }),
+        Call::Unfreeze(a, target, reason, reference) => call_unauthorized(|| {
+            client.unfreeze(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &id(target),
+                reason,
+                &reference.as_ref().map(bytes),
+            )
+        }),
+        Call::Decimals => call(|| {
+            client.decimals();
+        }),
+        Call::Name => call(|| {
+            client.name();
+        }),
+        Call::Symbol => call(|| {
+            client.symbol();
+        }),
+        Call::Delegate(a, delegatee) => call_unauthorized(|| {
+            client.delegate(&sig(a), &nonce(a), &a.valid_until, &id(delegatee))
+        }),
+        Call::Votes(a) => call(|| {
+            client.votes(&id(a));
+        }),
+        Call::VotesAt(a, ledger) => call(|| {
+            client.votes_at(&id(a), ledger);
+        }),
+        Call::XferRelay(a, to, amount, relayer, fee) => call_unauthorized(|| {
+            client.xfer_relay(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &id(to),
+                &int(amount),
+                &id(relayer),
+                &int(fee),
+            )
+        }),
+        Call::SetFee(a, fee_bps, min_fee, max_fee, collector) => call_unauthorized(|| {
+            client.set_fee(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                fee_bps,
+                &int(min_fee),
+                &int(max_fee),
+                &id(collector),
+            )
+        }),
+        Call::SetExempt(a, target, exempt) => call_unauthorized(|| {
+            client.set_exempt(&sig(a), &nonce(a), &a.valid_until, &id(target), exempt)
+        }),
+        Call::QuoteXfer(from, to, amount) => call(|| {
+            client.quote_xfer(&id(from), &id(to), &int(amount));
+        }),
+        Call::SharesOf(a) => call(|| {
+            client.shares_of(&id(a));
+        }),
+        Call::Index => call(|| {
+            client.index();
+        }),
+        Call::SetOracle(a, oracle) => {
+            call_unauthorized(|| client.set_oracle(&sig(a), &nonce(a), &a.valid_until, &id(oracle)))
+        }
+        Call::Rebase(a, index) => {
+            call_unauthorized(|| client.rebase(&sig(a), &nonce(a), &a.valid_until, &int(index)))
+        }
+        Call::SetRate(a, rate) => {
+            call_unauthorized(|| client.set_rate(&sig(a), &nonce(a), &a.valid_until, &int(rate)))
+        }
+        Call::Accrued(a) => call(|| {
+            client.accrued(&id(a));
+        }),
+        Call::Supply => call(|| {
+            client.supply();
+        }),
+        Call::Deposit(a, amount) => {
+            call_unauthorized(|| client.deposit(&sig(a), &nonce(a), &a.valid_until, &int(amount)))
+        }
+        Call::Withdraw(a, amount) => {
+            call_unauthorized(|| client.withdraw(&sig(a), &nonce(a), &a.valid_until, &int(amount)))
+        }
+        Call::SetMeta(a, name, symbol) => call_unauthorized(|| {
+            client.set_meta(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &bytes(name),
+                &bytes(symbol),
+            )
+        }),
+        Call::SetField(a, field, value) => call_unauthorized(|| {
+            client.set_field(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &meta_field(*field),
+                &bytes(value),
+            )
+        }),
+        Call::Metadata => call(|| {
+            client.metadata();
+        }),
+        Call::Version => call(|| {
+            client.version();
+        }),
+        Call::Migrate(a) => {
+            call_unauthorized(|| client.migrate(&sig(a), &nonce(a), &a.valid_until))
+        }
+        Call::Invalidate(a, up_to) => {
+            call_unauthorized(|| client.invalidate(&sig(a), &nonce(a), &a.valid_until, &int(up_to)))
+        }
+        Call::HolderCnt => call(|| {
+            client.holder_cnt();
+        }),
+        Call::Holders(start, limit) => call(|| {
+            client.holders(start, limit);
+        }),
+        Call::Allowances(from, start, limit) => call(|| {
+            client.allowances(&id(from), start, limit);
+        }),
+        Call::RevokeAll(a) => {
+            call_unauthorized(|| client.revoke_all(&sig(a), &nonce(a), &a.valid_until))
+        }
+        Call::FreezeRec(a) => call(|| {
+            client.freeze_rec(&id(a));
+        }),
+        Call::FreezeLog(a) => call(|| {
+            client.freeze_log(&id(a));
+        }),
+        Call::SetComply(a, compliance) => call_unauthorized(|| {
+            client.set_comply(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &compliance.map(|c| BytesN::from_array(e, &c)),
+            )
+        }),
+        Call::CheckXfer(from, to, amount) => call(|| {
+            client.check_xfer(&id(from), &id(to), &int(amount));
+        }),
+        Call::SetMax(a, max) => call_unauthorized(|| {
+            client.set_max(&sig(a), &nonce(a), &a.valid_until, &max.as_ref().map(int))
+        }),
+        Call::SetMaxId(a, target, max) => call_unauthorized(|| {
+            client.set_max_id(
+                &sig(a),
+                &nonce(a),
+                &a.valid_until,
+                &id(target),
+                &max.as_ref().map(int),
+            )
+        }),
+        Call::SetMaxEx(a, target, exempt) => call_unauthorized(|| {
+            client.set_max_ex(&sig(a), &nonce(a), &a.valid_until, &id(target), exempt)
+        }),
+        Call::MaxBal(a) => call(|| {
+            client.max_bal(&id(a));
+        }),
+        Call::SetLimit(a, target, limit) => call_unauthorized(|| {
+            let limit = limit.as_ref().map(|(amount, window)| VelocityLimit {
+                amount: int(amount),
+                window: *window,
+            });
+            client.set_limit(&sig(a), &nonce(a), &a.valid_until, &id(target), &limit)
+        }),
+        Call::Remaining(a) => call(|| {
+            client.remaining(&id(a));
+        }),
+        Call::SetTlock(a, timelock) => call_unauthorized(|| {
+            let timelock = timelock.as_ref().map(|(delay, mint_max)| Timelock {
+                delay: *delay,
+                mint_max: int(mint_max),
+            });
+            client.set_tlock(&sig(a), &nonce(a), &a.valid_until, &timelock)
+        }),
+        Call::Queue(a, action) => call_unauthorized(|| {
+            let action = match action {
+                QueueAction::Mint(to, amount) => Action::Mint(MintAction {
+                    to: id(to),
+                    amount: int(amount),
+                }),
+                QueueAction::SetAdmin(new_admin) => Action::SetAdmin(id(new_admin)),
+                QueueAction::Rebase(index) => Action::Rebase(int(index)),
+                QueueAction::Unlock => Action::Unlock,
+            };
+            client.queue(&sig(a), &nonce(a), &a.valid_until, &action);
+        }),
+        Call::Execute(a, queued) => {
+            call_unauthorized(|| client.execute(&sig(a), &nonce(a), &a.valid_until, queued))
+        }
+        Call::Cancel(a, queued) => {
+            call_unauthorized(|| client.cancel(&sig(a), &nonce(a), &a.valid_until, queued))
+        }
+        Call::Queued => call(|| {
+            client.queued();
+        }),
+    };
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    admin: ArbitraryIdentifier,
+    decimal: u32,
+    name: Vec<u8>,
+    symbol: Vec<u8>,
+    calls: Vec<Call>,
+}
+
+fuzz_target!(|input: Input| {
+    let e: Env = Default::default();
+    let contract_id = [0; 32];
+    register_token(&e, &contract_id);
+    let client = TokenClient::new(&e, &BytesN::from_array(&e, &contract_id));
+    let initialized = call(|| {
+        client.initialize(
+            &input.admin.identifier(&e),
+            &input.decimal,
+            &Bytes::from_slice(&e, &input.name),
+            &Bytes::from_slice(&e, &input.symbol),
+        )
+    });
+    if !initialized {
+        return;
+    }
+    for c in input.calls.iter() {
+        apply(&e, &client, c);
+    }
+});
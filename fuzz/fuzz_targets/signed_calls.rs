@@ -0,0 +1,263 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use libfuzzer_sys::fuzz_target;
+use soroban_auth::Identifier;
+use soroban_sdk::testutils::{Ledger, LedgerInfo};
+use soroban_sdk::Env;
+use soroban_token_contract::arbitrary::{ArbitraryBigInt, ArbitraryIdentifier};
+use soroban_token_contract::testutils::{
+    register_test_contract as register_token, to_ed25519, Token,
+};
+use soroban_token_contract::{Action, FreezeMode, MetaField, MintAction, Timelock, VelocityLimit};
+use soroban_token_contract_fuzz::call;
+
+// The first signer is the admin.
+const SIGNERS: usize = 4;
+
+#[derive(Arbitrary, Debug)]
+enum Who {
+    Signer(u8),
+    Other(ArbitraryIdentifier),
+}
+
+#[derive(Arbitrary, Debug)]
+enum Call {
+    Approve(u8, Who, ArbitraryBigInt),
+    Xfer(u8, Who, ArbitraryBigInt),
+    XferFrom(u8, Who, Who, ArbitraryBigInt),
+    Burn(u8, Who, ArbitraryBigInt),
+    Freeze(u8, Who, u8, u32, Option<String>),
+    Mint(u8, Who, ArbitraryBigInt),
+    SetAdmin(u8, Who),
+    Unfreeze(u8, Who, u32, Option<String>),
+    Delegate(u8, Who),
+    XferRelay(u8, Who, ArbitraryBigInt, Who, ArbitraryBigInt),
+    SetFee(u8, u32, ArbitraryBigInt, ArbitraryBigInt, Who),
+    SetExempt(u8, Who, bool),
+    SetOracle(u8, Who),
+    Rebase(u8, ArbitraryBigInt),
+    SetRate(u8, ArbitraryBigInt),
+    Deposit(u8, ArbitraryBigInt),
+    Withdraw(u8, ArbitraryBigInt),
+    SetMeta(u8, String, String),
+    SetField(u8, u8, String),
+    Migrate(u8),
+    Invalidate(u8, ArbitraryBigInt),
+    RevokeAll(u8),
+    SetMax(u8, Option<ArbitraryBigInt>),
+    SetMaxId(u8, Who, Option<ArbitraryBigInt>),
+    SetMaxEx(u8, Who, bool),
+    SetLimit(u8, Who, Option<(ArbitraryBigInt, u64)>),
+    SetTlock(u8, Option<(u64, ArbitraryBigInt)>),
+    Queue(u8, QueueAction),
+    Execute(u8, u32),
+    Cancel(u8, u32),
+    // Moves the ledger forward, so that interest accrues and signatures
+    // expire.
+    Advance(u32, u32),
+}
+
+#[derive(Arbitrary, Debug)]
+enum QueueAction {
+    Mint(Who, ArbitraryBigInt),
+    SetAdmin(Who),
+    Rebase(ArbitraryBigInt),
+    Unlock,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Step {
+    lane: u64,
+    valid_until: Option<u32>,
+    call: Call,
+}
+
+// Fixed keys keep crashes reproducible.
+fn keypair(n: usize) -> Keypair {
+    let secret = SecretKey::from_bytes(&[n as u8 + 1; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+struct Harness {
+    e: Env,
+    token: Token,
+    signers: Vec<Keypair>,
+}
+
+impl Harness {
+    fn signer(&self, n: u8) -> &Keypair {
+        &self.signers[n as usize % SIGNERS]
+    }
+
+    fn id(&self, who: &Who) -> Identifier {
+        match who {
+            Who::Signer(n) => to_ed25519(&self.e, self.signer(*n)),
+            Who::Other(id) => id.identifier(&self.e),
+        }
+    }
+
+    fn apply(&self, step: &Step) {
+        let e = &self.e;
+        let token = self
+            .token
+            .with_lane(step.lane)
+            .with_valid_until(step.valid_until);
+        let s = |n: &u8| self.signer(*n);
+        let id = |w: &Who| self.id(w);
+        let int = |a: &ArbitraryBigInt| a.bigint(e);
+        match &step.call {
+            Call::Approve(n, spender, amount) => {
+                call(|| token.approve(s(n), &id(spender), &int(amount)));
+            }
+            Call::Xfer(n, to, amount) => {
+                call(|| token.xfer(s(n), &id(to), &int(amount)));
+            }
+            Call::XferFrom(n, from, to, amount) => {
+                call(|| token.xfer_from(s(n), &id(from), &id(to), &int(amount)));
+            }
+            Call::Burn(n, from, amount) => {
+                call(|| token.burn(s(n), &id(from), &int(amount)));
+            }
+            Call::Freeze(n, target, mode, reason, reference) => {
+                let mode = match mode % 4 {
+                    0 => FreezeMode::Off,
+                    1 => FreezeMode::Outbound,
+                    2 => FreezeMode::Inbound,
+                    _ => FreezeMode::Full,
+                };
+                call(|| token.freeze_with(s(n), &id(target), mode, *reason, reference.as_deref()));
+            }
+            Call::Mint(n, to, amount) => {
+                call(|| token.mint(s(n), &id(to), &int(amount)));
+            }
+            Call::SetAdmin(n, new_admin) => {
+                call(|| token.set_admin(s(n), &id(new_admin)));
+            }
+            Call::Unfreeze(n, target, reason, reference) => {
+                call(|| token.unfreeze_with(s(n), &id(target), *reason, reference.as_deref()));
+            }
+            Call::Delegate(n, delegatee) => {
+                call(|| token.delegate(s(n), &id(delegatee)));
+            }
+            Call::XferRelay(n, to, amount, relayer, fee) => {
+                call(|| token.xfer_relay(s(n), &id(to), &int(amount), &id(relayer), &int(fee)));
+            }
+            Call::SetFee(n, fee_bps, min_fee, max_fee, collector) => {
+                call(|| {
+                    token.set_fee(s(n), *fee_bps, &int(min_fee), &int(max_fee), &id(collector))
+                });
+            }
+            Call::SetExempt(n, target, exempt) => {
+                call(|| token.set_exempt(s(n), &id(target), *exempt));
+            }
+            Call::SetOracle(n, oracle) => {
+                call(|| token.set_oracle(s(n), &id(oracle)));
+            }
+            Call::Rebase(n, index) => {
+                call(|| token.rebase(s(n), &int(index)));
+            }
+            Call::SetRate(n, rate) => {
+                call(|| token.set_rate(s(n), &int(rate)));
+            }
+            Call::Deposit(n, amount) => {
+                call(|| token.deposit(s(n), &int(amount)));
+            }
+            Call::Withdraw(n, amount) => {
+                call(|| token.withdraw(s(n), &int(amount)));
+            }
+            Call::SetMeta(n, name, symbol) => {
+                call(|| token.set_meta(s(n), name, symbol));
+            }
+            Call::SetField(n, field, value) => {
+                let field = match field % 4 {
+                    0 => MetaField::IconUri,
+                    1 => MetaField::HomeDomain,
+                    2 => MetaField::Desc,
+                    _ => MetaField::IssuerUrl,
+                };
+                call(|| token.set_field(s(n), field, value));
+            }
+            Call::Migrate(n) => {
+                call(|| token.migrate(s(n)));
+            }
+            Call::Invalidate(n, up_to) => {
+                call(|| token.invalidate(s(n), &int(up_to)));
+            }
+            Call::RevokeAll(n) => {
+                call(|| token.revoke_all(s(n)));
+            }
+            Call::SetMax(n, max) => {
+                call(|| token.set_max(s(n), max.as_ref().map(int).as_ref()));
+            }
+            Call::SetMaxId(n, target, max) => {
+                call(|| token.set_max_id(s(n), &id(target), max.as_ref().map(int).as_ref()));
+            }
+            Call::SetMaxEx(n, target, exempt) => {
+                call(|| token.set_max_ex(s(n), &id(target), *exempt));
+            }
+            Call::SetLimit(n, target, limit) => {
+                let limit = limit.as_ref().map(|(amount, window)| VelocityLimit {
+                    amount: int(amount),
+                    window: *window,
+                });
+                call(|| token.set_limit(s(n), &id(target), limit));
+            }
+            Call::SetTlock(n, timelock) => {
+                let timelock = timelock.as_ref().map(|(delay, mint_max)| Timelock {
+                    delay: *delay,
+                    mint_max: int(mint_max),
+                });
+                call(|| token.set_tlock(s(n), timelock));
+            }
+            Call::Queue(n, action) => {
+                let action = match action {
+                    QueueAction::Mint(to, amount) => Action::Mint(MintAction {
+                        to: id(to),
+                        amount: int(amount),
+                    }),
+                    QueueAction::SetAdmin(new_admin) => Action::SetAdmin(id(new_admin)),
+                    QueueAction::Rebase(index) => Action::Rebase(int(index)),
+                    QueueAction::Unlock => Action::Unlock,
+                };
+                call(|| {
+                    token.queue(s(n), action);
+                });
+            }
+            Call::Execute(n, queued) => {
+                call(|| token.execute(s(n), *queued));
+            }
+            Call::Cancel(n, queued) => {
+                call(|| token.cancel(s(n), *queued));
+            }
+            Call::Advance(ledgers, seconds) => {
+                let sequence_number = e.ledger().sequence().saturating_add(*ledgers);
+                let timestamp = e.ledger().timestamp().saturating_add(*seconds as u64);
+                e.ledger().set(LedgerInfo {
+                    protocol_version: 1,
+                    sequence_number,
+                    timestamp,
+                    network_passphrase: vec![0],
+                    base_reserve: 10,
+                });
+            }
+        }
+    }
+}
+
+fuzz_target!(|steps: Vec<Step>| {
+    let e: Env = Default::default();
+    let contract_id = [0; 32];
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let signers: Vec<Keypair> = (0..SIGNERS).map(keypair).collect();
+    token.initialize(&to_ed25519(&e, &signers[0]), 7, "name", "symbol");
+
+    let harness = Harness { e, token, signers };
+    for step in steps.iter() {
+        harness.apply(step);
+    }
+});
@@ -0,0 +1,111 @@
+//! Shared support for the fuzz targets, which are run with
+//! `cargo +nightly fuzz run <target>` from the repository root.
+//!
+//! `raw_calls` calls every entry point with arbitrary identifiers, amounts
+//! and signatures, so signed calls are expected to fail authorization.
+//! `signed_calls` signs its calls with known keys, so arbitrary arguments
+//! reach the contract logic.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+/// Panics the token raises on invalid input. Any other panic is a bug.
+pub const DOCUMENTED_PANICS: &[&str] = &[
+    "already initialized",
+    "not authorized by admin",
+    "not authorized by admin or oracle",
+    "insufficient allowance",
+    "insufficient balance",
+    "fee must not be negative",
+    "amount must not be negative",
+    "amount must be positive",
+    "nothing received from the underlying token",
+    "not allowed on a wrapped token",
+    "can't receive when frozen",
+    "can't spend when frozen",
+    "signature expired",
+    "nonces already used",
+    "nonce out of range",
+    "nonce lane exhausted",
+    "fee_bps must not exceed 10000",
+    "min_fee must not be negative",
+    "max_fee must not be less than min_fee",
+    "rate must not be negative",
+    "index must be positive",
+    "Decimal must fit in a u8",
+    "decimal must not exceed 18",
+    "name must not be empty",
+    "name must not exceed 64 bytes",
+    "name must be printable ASCII",
+    "symbol must not be empty",
+    "symbol must not exceed 12 bytes",
+    "symbol must be printable ASCII without spaces",
+    "already at latest version",
+    "not a wrapped token",
+    "transfer restricted by compliance",
+    "max balance must not be negative",
+    "balance exceeds maximum",
+    "limit must not be negative",
+    "velocity limit exceeded",
+    "mint_max must not be negative",
+    "action must be queued",
+    "timelock not enabled",
+    "no queued action",
+    "action not ready",
+];
+
+/// Authorization failures raised by `soroban_auth::check_auth`, and by the
+/// host when it verifies an ed25519 signature. Any other `HostError` is a
+/// bug.
+pub const AUTH_PANICS: &[&str] = &[
+    "incorrect nonce",
+    "nonce should be zero for Invoker",
+    "Invoker does not match Signature",
+    "signature out of order",
+    "insufficient signing weight",
+    "Failed ED25519 verification",
+    "invalid ed25519 public key",
+];
+
+static QUIET: Once = Once::new();
+
+fn message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        ""
+    }
+}
+
+fn run(f: impl FnOnce(), allowed: &[&[&str]]) -> bool {
+    // libfuzzer aborts on any panic, so expected panics are caught here and
+    // only undocumented ones abort.
+    QUIET.call_once(|| panic::set_hook(Box::new(|_| {})));
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(f)) {
+        let msg = message(payload.as_ref());
+        if !allowed
+            .iter()
+            .flat_map(|l| l.iter())
+            .any(|m| msg.contains(m))
+        {
+            eprintln!("undocumented panic: {:?}", msg);
+            std::process::abort();
+        }
+        return false;
+    }
+    true
+}
+
+/// Runs a call that is expected to pass authorization, returning whether it
+/// succeeded.
+pub fn call(f: impl FnOnce()) -> bool {
+    run(f, &[DOCUMENTED_PANICS])
+}
+
+/// Runs a call whose authorization may fail, returning whether it succeeded.
+pub fn call_unauthorized(f: impl FnOnce()) -> bool {
+    run(f, &[DOCUMENTED_PANICS, AUTH_PANICS])
+}
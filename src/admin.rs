@@ -7,7 +7,7 @@ pub fn has_administrator(e: &Env) -> bool {
     e.contract_data().has(key)
 }
 
-fn read_administrator(e: &Env) -> Identifier {
+pub fn read_administrator(e: &Env) -> Identifier {
     let key = DataKey::Admin;
     e.contract_data().get_unchecked(key).unwrap()
 }
@@ -1,6 +1,13 @@
 use crate::storage_types::{AllowanceDataKey, DataKey};
 use soroban_auth::Identifier;
-use soroban_sdk::{BigInt, Env};
+use soroban_sdk::{contracttype, BigInt, Env, Vec};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Approval {
+    pub spender: Identifier,
+    pub amount: BigInt,
+}
 
 pub fn read_allowance(e: &Env, from: Identifier, spender: Identifier) -> BigInt {
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
@@ -12,6 +19,7 @@ pub fn read_allowance(e: &Env, from: Identifier, spender: Identifier) -> BigInt
 }
 
 pub fn write_allowance(e: &Env, from: Identifier, spender: Identifier, amount: BigInt) {
+    update_spenders(e, from.clone(), spender.clone(), &amount);
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
     e.contract_data().set(key, amount);
 }
@@ -23,3 +31,66 @@ pub fn spend_allowance(e: &Env, from: Identifier, spender: Identifier, amount: B
     }
     write_allowance(e, from, spender, allowance - amount);
 }
+
+// Each owner's spenders with a non-zero allowance, in the order they were
+// approved. Approvals made before the index existed are added the next time
+// they are written.
+fn read_spenders(e: &Env, from: Identifier) -> Vec<Identifier> {
+    let key = DataKey::Spenders(from);
+    if let Some(spenders) = e.contract_data().get(key) {
+        spenders.unwrap()
+    } else {
+        Vec::new(e)
+    }
+}
+
+fn update_spenders(e: &Env, from: Identifier, spender: Identifier, amount: &BigInt) {
+    let spenders = read_spenders(e, from.clone());
+    let approved = *amount != BigInt::zero(e);
+    let listed = spenders.iter().any(|s| s.unwrap() == spender);
+    let updated = match (approved, listed) {
+        (true, false) => {
+            let mut updated = spenders;
+            updated.push_back(spender);
+            updated
+        }
+        (false, true) => {
+            let mut updated = Vec::new(e);
+            for s in spenders.iter() {
+                let s = s.unwrap();
+                if s != spender {
+                    updated.push_back(s);
+                }
+            }
+            updated
+        }
+        _ => return,
+    };
+    let key = DataKey::Spenders(from);
+    e.contract_data().set(key, updated);
+}
+
+pub fn read_approvals(e: &Env, from: Identifier, start: u32, limit: u32) -> Vec<Approval> {
+    let spenders = read_spenders(e, from.clone());
+    let end = spenders.len().min(start.saturating_add(limit));
+    let mut approvals = Vec::new(e);
+    for i in start..end {
+        let spender = spenders.get_unchecked(i).unwrap();
+        let amount = read_allowance(e, from.clone(), spender.clone());
+        approvals.push_back(Approval { spender, amount });
+    }
+    approvals
+}
+
+/// Zeroes every allowance `from` has granted.
+pub fn revoke_allowances(e: &Env, from: Identifier) {
+    for spender in read_spenders(e, from.clone()).iter() {
+        let key = DataKey::Allowance(AllowanceDataKey {
+            from: from.clone(),
+            spender: spender.unwrap(),
+        });
+        e.contract_data().set(key, BigInt::zero(e));
+    }
+    let key = DataKey::Spenders(from);
+    e.contract_data().set(key, Vec::<Identifier>::new(e));
+}
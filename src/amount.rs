@@ -0,0 +1,120 @@
+#![cfg(feature = "amount")]
+
+//! Conversions between raw token units and decimal strings such as
+//! "12.3456789", for off-chain clients and tests. Enabled by the `amount`
+//! feature, which only needs std.
+
+use soroban_sdk::{BigInt, Env};
+use std::string::String;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AmountError {
+    /// The string has no digits.
+    Empty,
+    /// The string contains something other than a sign, digits and a
+    /// single decimal point.
+    InvalidDigit,
+    /// The string has more fractional digits than the token's decimals.
+    TooPrecise,
+}
+
+impl std::fmt::Display for AmountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AmountError::Empty => write!(f, "amount is empty"),
+            AmountError::InvalidDigit => write!(f, "amount is not a decimal number"),
+            AmountError::TooPrecise => write!(f, "amount has too many decimal places"),
+        }
+    }
+}
+
+impl std::error::Error for AmountError {}
+
+fn pow10(e: &Env, n: u32) -> BigInt {
+    let ten = BigInt::from_u32(e, 10);
+    let mut result = BigInt::from_u32(e, 1);
+    for _ in 0..n {
+        result = result * ten.clone();
+    }
+    result
+}
+
+fn parse_digits(e: &Env, digits: &str) -> Result<BigInt, AmountError> {
+    let ten = BigInt::from_u32(e, 10);
+    let mut result = BigInt::zero(e);
+    for c in digits.chars() {
+        let digit = c.to_digit(10).ok_or(AmountError::InvalidDigit)?;
+        result = result * ten.clone() + BigInt::from_u32(e, digit);
+    }
+    Ok(result)
+}
+
+/// Parses a decimal string such as "-12.5" into raw units at `decimals`.
+/// Trailing zeros past `decimals` are accepted; any other extra precision
+/// is rejected rather than rounded.
+pub fn parse_amount(e: &Env, s: &str, decimals: u32) -> Result<BigInt, AmountError> {
+    let (negative, unsigned) = if let Some(rest) = s.strip_prefix('-') {
+        (true, rest)
+    } else {
+        (false, s)
+    };
+    let (whole, fraction) = if let Some((whole, fraction)) = unsigned.split_once('.') {
+        (whole, fraction)
+    } else {
+        (unsigned, "")
+    };
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(AmountError::Empty);
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        if fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err(AmountError::TooPrecise);
+        }
+        return Err(AmountError::InvalidDigit);
+    }
+    let scale = decimals - fraction.len() as u32;
+    let amount =
+        parse_digits(e, whole)? * pow10(e, decimals) + parse_digits(e, fraction)? * pow10(e, scale);
+    if negative {
+        Ok(BigInt::zero(e) - amount)
+    } else {
+        Ok(amount)
+    }
+}
+
+/// Formats raw units at `decimals` as a decimal string, without trailing
+/// zeros in the fraction.
+pub fn format_amount(e: &Env, amount: &BigInt, decimals: u32) -> String {
+    let zero = BigInt::zero(e);
+    let ten = BigInt::from_u32(e, 10);
+    let negative = *amount < zero;
+    let mut rest = if negative {
+        zero.clone() - amount.clone()
+    } else {
+        amount.clone()
+    };
+
+    // Digits are collected least significant first.
+    let mut digits = String::new();
+    while rest > zero || digits.len() <= decimals as usize {
+        let digit = (rest.clone() % ten.clone()).to_u64() as u8;
+        digits.push((b'0' + digit) as char);
+        rest = rest / ten.clone();
+    }
+    let digits: String = digits.chars().rev().collect();
+
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    let mut result = String::new();
+    if negative {
+        result.push('-');
+    }
+    result.push_str(whole);
+    if !fraction.is_empty() {
+        result.push('.');
+        result.push_str(fraction);
+    }
+    result
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "testutils")]
+
+//! Adapters that build contract arguments from fuzzer input. Every value they
+//! produce is well-formed for the host, but not necessarily meaningful to the
+//! contract: identifiers need not exist, signatures need not verify and
+//! amounts can be negative or far larger than any balance.
+
+use ::arbitrary::Arbitrary;
+use soroban_auth::{Ed25519Signature, Identifier, Signature};
+use soroban_sdk::{BigInt, BytesN, Env};
+
+// Caps the size of generated amounts, which is still far beyond `i128`.
+const MAX_LIMBS: usize = 8;
+
+#[derive(Arbitrary, Clone, Debug)]
+pub enum ArbitraryIdentifier {
+    Contract([u8; 32]),
+    Ed25519([u8; 32]),
+}
+
+impl ArbitraryIdentifier {
+    pub fn identifier(&self, e: &Env) -> Identifier {
+        match self {
+            ArbitraryIdentifier::Contract(id) => Identifier::Contract(BytesN::from_array(e, id)),
+            ArbitraryIdentifier::Ed25519(key) => Identifier::Ed25519(BytesN::from_array(e, key)),
+        }
+    }
+}
+
+#[derive(Arbitrary, Clone, Debug)]
+pub enum ArbitrarySignature {
+    Ed25519 {
+        public_key: [u8; 32],
+        signature: [u8; 64],
+    },
+    Invoker,
+}
+
+impl ArbitrarySignature {
+    pub fn signature(&self, e: &Env) -> Signature {
+        match self {
+            ArbitrarySignature::Ed25519 {
+                public_key,
+                signature,
+            } => Signature::Ed25519(Ed25519Signature {
+                public_key: BytesN::from_array(e, public_key),
+                signature: BytesN::from_array(e, signature),
+            }),
+            ArbitrarySignature::Invoker => Signature::Invoker,
+        }
+    }
+}
+
+/// A signed integer built from big-endian 64-bit limbs.
+#[derive(Arbitrary, Clone, Debug)]
+pub struct ArbitraryBigInt {
+    pub negative: bool,
+    pub limbs: std::vec::Vec<u64>,
+}
+
+impl ArbitraryBigInt {
+    pub fn bigint(&self, e: &Env) -> BigInt {
+        let base = BigInt::from_u64(e, u64::MAX) + BigInt::from_u32(e, 1);
+        let mut value = BigInt::zero(e);
+        for limb in self.limbs.iter().take(MAX_LIMBS) {
+            value = value * base.clone() + BigInt::from_u64(e, *limb);
+        }
+        if self.negative {
+            BigInt::zero(e) - value
+        } else {
+            value
+        }
+    }
+}
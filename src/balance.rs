@@ -1,52 +1,118 @@
+use crate::freeze::FreezeMode;
+use crate::holders::update_holder;
+use crate::interest::{
+    interest_on, read_account_index, read_interest_index, update_interest_index,
+    write_account_index,
+};
+use crate::max_balance::check_max_balance;
+use crate::rebase::{to_amount, to_shares_down, to_shares_up};
 use crate::storage_types::DataKey;
+use crate::supply::increase_supply;
+use crate::velocity::record_outflow;
+use crate::votes::{add_delegated_votes, sub_delegated_votes};
 use soroban_auth::Identifier;
 use soroban_sdk::{BigInt, Env};
 
-pub fn read_balance(e: &Env, id: Identifier) -> BigInt {
+pub fn read_shares(e: &Env, id: Identifier) -> BigInt {
     let key = DataKey::Balance(id);
-    if let Some(balance) = e.contract_data().get(key) {
-        balance.unwrap()
+    if let Some(shares) = e.contract_data().get(key) {
+        shares.unwrap()
     } else {
         BigInt::zero(e)
     }
 }
 
-fn write_balance(e: &Env, id: Identifier, amount: BigInt) {
+fn write_shares(e: &Env, id: Identifier, shares: BigInt) {
+    update_holder(e, id.clone(), &shares);
     let key = DataKey::Balance(id);
-    e.contract_data().set(key, amount);
+    e.contract_data().set(key, shares);
+}
+
+pub fn read_accrued_interest(e: &Env, id: Identifier) -> BigInt {
+    let amount = to_amount(e, read_shares(e, id.clone()));
+    interest_on(amount, read_account_index(e, id), read_interest_index(e))
+}
+
+pub fn read_balance(e: &Env, id: Identifier) -> BigInt {
+    let interest = read_accrued_interest(e, id.clone());
+    to_amount(e, read_shares(e, id) + to_shares_down(e, interest))
 }
 
-pub fn receive_balance(e: &Env, id: Identifier, amount: BigInt) {
-    let balance = read_balance(e, id.clone());
-    let is_frozen = read_state(e, id.clone());
-    if is_frozen {
+// Mints the interest `id` has accrued since it was last touched.
+fn accrue_interest(e: &Env, id: Identifier) {
+    let index = update_interest_index(e);
+    let shares = read_shares(e, id.clone());
+    let interest = interest_on(
+        to_amount(e, shares.clone()),
+        read_account_index(e, id.clone()),
+        index.clone(),
+    );
+    write_account_index(e, id.clone(), index);
+
+    let minted = to_shares_down(e, interest);
+    if minted > BigInt::zero(e) {
+        write_shares(e, id.clone(), shares + minted.clone());
+        add_delegated_votes(e, id, minted.clone());
+        increase_supply(e, minted);
+    }
+}
+
+/// Credits `amount` to `id` and returns the number of shares credited.
+pub fn receive_balance(e: &Env, id: Identifier, amount: BigInt) -> BigInt {
+    if amount < BigInt::zero(e) {
+        panic!("amount must not be negative");
+    }
+    if read_freeze_mode(e, id.clone()).blocks_inbound() {
         panic!("can't receive when frozen");
     }
-    write_balance(e, id, balance + amount);
+    accrue_interest(e, id.clone());
+    let balance = read_shares(e, id.clone());
+    let shares = to_shares_down(e, amount);
+    let balance = balance + shares.clone();
+    check_max_balance(e, id.clone(), &to_amount(e, balance.clone()));
+    write_shares(e, id.clone(), balance);
+    add_delegated_votes(e, id, shares.clone());
+    shares
 }
 
-pub fn spend_balance(e: &Env, id: Identifier, amount: BigInt) {
-    let balance = read_balance(e, id.clone());
-    let is_frozen = read_state(e, id.clone());
-    if is_frozen {
+/// Debits `amount` from `id` and returns the number of shares debited.
+pub fn spend_balance(e: &Env, id: Identifier, amount: BigInt) -> BigInt {
+    if amount < BigInt::zero(e) {
+        panic!("amount must not be negative");
+    }
+    if read_freeze_mode(e, id.clone()).blocks_outbound() {
         panic!("can't spend when frozen");
     }
-    if balance < amount {
+    record_outflow(e, id.clone(), amount.clone());
+    accrue_interest(e, id.clone());
+    let balance = read_shares(e, id.clone());
+    let shares = to_shares_up(e, amount);
+    if balance < shares {
         panic!("insufficient balance");
     }
-    write_balance(e, id, balance - amount);
+    write_shares(e, id.clone(), balance - shares.clone());
+    sub_delegated_votes(e, id, shares.clone());
+    shares
 }
 
-pub fn read_state(e: &Env, id: Identifier) -> bool {
+// Accounts frozen before freeze modes existed only have a `true` stored
+// under `DataKey::State`, which reads as a full freeze.
+pub fn read_freeze_mode(e: &Env, id: Identifier) -> FreezeMode {
+    let key = DataKey::Freeze(id.clone());
+    if let Some(mode) = e.contract_data().get(key) {
+        return mode.unwrap();
+    }
     let key = DataKey::State(id);
     if let Some(state) = e.contract_data().get(key) {
-        state.unwrap()
-    } else {
-        false
+        if state.unwrap() {
+            return FreezeMode::Full;
+        }
     }
+    FreezeMode::Off
 }
 
-pub fn write_state(e: &Env, id: Identifier, is_frozen: bool) {
-    let key = DataKey::State(id);
-    e.contract_data().set(key, is_frozen);
+pub fn write_freeze_mode(e: &Env, id: Identifier, mode: FreezeMode) {
+    e.contract_data().remove(DataKey::State(id.clone()));
+    let key = DataKey::Freeze(id);
+    e.contract_data().set(key, mode);
 }
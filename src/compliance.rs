@@ -0,0 +1,43 @@
+use crate::storage_types::DataKey;
+use soroban_auth::Identifier;
+use soroban_sdk::{symbol, BigInt, BytesN, Env, IntoVal};
+
+// A compliance contract implements
+// `can_transfer(from: Identifier, to: Identifier, amount: BigInt) -> u32`,
+// returning zero to allow the transfer or a non-zero restriction code.
+
+pub fn read_compliance(e: &Env) -> Option<BytesN<32>> {
+    let key = DataKey::Compliance;
+    if let Some(compliance) = e.contract_data().get(key) {
+        Some(compliance.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn write_compliance(e: &Env, compliance: Option<BytesN<32>>) {
+    let key = DataKey::Compliance;
+    if let Some(compliance) = compliance {
+        e.contract_data().set(key, compliance);
+    } else {
+        e.contract_data().remove(key);
+    }
+}
+
+pub fn check_transfer(e: &Env, from: Identifier, to: Identifier, amount: BigInt) -> u32 {
+    if let Some(compliance) = read_compliance(e) {
+        e.invoke_contract(
+            &compliance,
+            &symbol!("can_transfer"),
+            (from, to, amount).into_val(e),
+        )
+    } else {
+        0
+    }
+}
+
+pub fn enforce_transfer(e: &Env, from: Identifier, to: Identifier, amount: BigInt) {
+    if check_transfer(e, from, to, amount) != 0 {
+        panic!("transfer restricted by compliance");
+    }
+}
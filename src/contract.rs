@@ -1,74 +1,380 @@
-use crate::admin::{check_admin, has_administrator, write_administrator};
-use crate::allowance::{read_allowance, spend_allowance, write_allowance};
-use crate::balance::{read_balance, receive_balance, spend_balance};
-use crate::balance::{read_state, write_state};
+use crate::admin::{check_admin, has_administrator, read_administrator, write_administrator};
+use crate::allowance::{
+    read_allowance, read_approvals, revoke_allowances, spend_allowance, write_allowance, Approval,
+};
+use crate::balance::{
+    read_accrued_interest, read_balance, read_shares, receive_balance, spend_balance,
+};
+use crate::balance::{read_freeze_mode, write_freeze_mode};
+use crate::compliance::{check_transfer, enforce_transfer, write_compliance};
+use crate::event;
+use crate::fee::{compute_fee, read_fee_config, write_fee_config, write_fee_exempt, XferQuote};
+use crate::freeze::{
+    read_freeze_log, read_freeze_record, write_freeze_record, FreezeMode, FreezeRecord,
+};
+use crate::holders::{read_holder_count, read_holders};
+use crate::interest::write_rate;
+use crate::max_balance::{read_limit, write_account_max, write_max_balance, write_max_exempt};
 use crate::metadata::{
-    read_decimal, read_name, read_symbol, write_decimal, write_name, write_symbol,
+    check_decimal, check_name, check_symbol, read_decimal, read_metadata, read_name, read_symbol,
+    write_decimal, write_field, write_name, write_symbol, MetaField, TokenMetadata,
+};
+use crate::migrate::{migrate_storage, read_version, write_version, CURRENT_VERSION};
+use crate::rebase::{read_index, read_oracle, to_amount, write_index, write_oracle};
+use crate::storage_types::{DataKey, FeeConfig, NonceKey};
+use crate::supply::{decrease_supply, increase_supply, read_supply};
+use crate::timelock::{
+    check_unlocked, dequeue, enqueue, read_queue, use_mint_allowance, write_timelock, Action,
+    QueuedAction, Timelock,
 };
-use crate::storage_types::DataKey;
+use crate::velocity::{read_remaining, write_velocity_limit, VelocityLimit};
+use crate::votes::{
+    add_delegated_votes, read_votes, read_votes_at, sub_delegated_votes, write_delegate,
+};
+use crate::wrapper::{check_not_wrapped, read_underlying, write_underlying};
 use soroban_auth::{check_auth, NonceAuth};
 use soroban_auth::{Identifier, Signature};
-use soroban_sdk::{contractimpl, symbol, BigInt, Bytes, Env, IntoVal};
+use soroban_sdk::{contractimpl, symbol, BigInt, Bytes, BytesN, Env, IntoVal, Vec};
 
 pub trait TokenTrait {
     fn initialize(e: Env, admin: Identifier, decimal: u32, name: Bytes, symbol: Bytes);
 
-    fn nonce(e: Env, id: Identifier) -> BigInt;
+    fn init_wrap(e: Env, admin: Identifier, underlying: BytesN<32>, name: Bytes, symbol: Bytes);
+
+    fn nonce(e: Env, id: Identifier, lane: Option<u64>) -> BigInt;
 
     fn allowance(e: Env, from: Identifier, spender: Identifier) -> BigInt;
 
-    fn approve(e: Env, from: Signature, nonce: BigInt, spender: Identifier, amount: BigInt);
+    fn approve(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        spender: Identifier,
+        amount: BigInt,
+    );
 
     fn balance(e: Env, id: Identifier) -> BigInt;
 
-    fn is_frozen(e: Env, id: Identifier) -> bool;
+    fn is_frozen(e: Env, id: Identifier) -> FreezeMode;
 
-    fn xfer(e: Env, from: Signature, nonce: BigInt, to: Identifier, amount: BigInt);
+    fn xfer(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        to: Identifier,
+        amount: BigInt,
+    );
 
     fn xfer_from(
         e: Env,
         spender: Signature,
         nonce: BigInt,
+        valid_until: Option<u32>,
         from: Identifier,
         to: Identifier,
         amount: BigInt,
     );
 
-    fn burn(e: Env, admin: Signature, nonce: BigInt, from: Identifier, amount: BigInt);
+    fn burn(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        from: Identifier,
+        amount: BigInt,
+    );
 
-    fn freeze(e: Env, admin: Signature, nonce: BigInt, id: Identifier);
+    fn freeze(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        mode: FreezeMode,
+        reason: u32,
+        reference: Option<Bytes>,
+    );
 
-    fn mint(e: Env, admin: Signature, nonce: BigInt, to: Identifier, amount: BigInt);
+    fn mint(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        to: Identifier,
+        amount: BigInt,
+    );
 
-    fn set_admin(e: Env, admin: Signature, nonce: BigInt, new_admin: Identifier);
+    fn set_admin(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        new_admin: Identifier,
+    );
 
-    fn unfreeze(e: Env, admin: Signature, nonce: BigInt, id: Identifier);
+    fn unfreeze(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        reason: u32,
+        reference: Option<Bytes>,
+    );
 
     fn decimals(e: Env) -> u32;
 
     fn name(e: Env) -> Bytes;
 
     fn symbol(e: Env) -> Bytes;
+
+    fn delegate(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        delegatee: Identifier,
+    );
+
+    /// Returns the votes delegated to `id`, counted in shares (see
+    /// `shares_of`) rather than balance, so that a rebase leaves voting power
+    /// unchanged.
+    fn votes(e: Env, id: Identifier) -> BigInt;
+
+    /// Returns the votes delegated to `id` as of `ledger`, in shares.
+    fn votes_at(e: Env, id: Identifier, ledger: u32) -> BigInt;
+
+    fn xfer_relay(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        to: Identifier,
+        amount: BigInt,
+        relayer: Identifier,
+        fee: BigInt,
+    );
+
+    fn set_fee(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        fee_bps: u32,
+        min_fee: BigInt,
+        max_fee: BigInt,
+        collector: Identifier,
+    );
+
+    fn set_exempt(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        exempt: bool,
+    );
+
+    fn quote_xfer(e: Env, from: Identifier, to: Identifier, amount: BigInt) -> XferQuote;
+
+    fn shares_of(e: Env, id: Identifier) -> BigInt;
+
+    fn index(e: Env) -> BigInt;
+
+    fn set_oracle(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        oracle: Identifier,
+    );
+
+    fn rebase(e: Env, auth: Signature, nonce: BigInt, valid_until: Option<u32>, index: BigInt);
+
+    fn set_rate(e: Env, admin: Signature, nonce: BigInt, valid_until: Option<u32>, rate: BigInt);
+
+    fn accrued(e: Env, id: Identifier) -> BigInt;
+
+    fn supply(e: Env) -> BigInt;
+
+    fn deposit(e: Env, from: Signature, nonce: BigInt, valid_until: Option<u32>, amount: BigInt);
+
+    fn withdraw(e: Env, from: Signature, nonce: BigInt, valid_until: Option<u32>, amount: BigInt);
+
+    fn set_meta(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        name: Bytes,
+        symbol: Bytes,
+    );
+
+    fn set_field(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        field: MetaField,
+        value: Bytes,
+    );
+
+    fn metadata(e: Env) -> TokenMetadata;
+
+    fn version(e: Env) -> u32;
+
+    fn migrate(e: Env, admin: Signature, nonce: BigInt, valid_until: Option<u32>);
+
+    fn invalidate(e: Env, from: Signature, nonce: BigInt, valid_until: Option<u32>, up_to: BigInt);
+
+    fn holder_cnt(e: Env) -> u32;
+
+    fn holders(e: Env, start: u32, limit: u32) -> Vec<Identifier>;
+
+    fn allowances(e: Env, from: Identifier, start: u32, limit: u32) -> Vec<Approval>;
+
+    fn revoke_all(e: Env, from: Signature, nonce: BigInt, valid_until: Option<u32>);
+
+    fn freeze_rec(e: Env, id: Identifier) -> Option<FreezeRecord>;
+
+    fn freeze_log(e: Env, id: Identifier) -> Vec<FreezeRecord>;
+
+    fn set_comply(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        compliance: Option<BytesN<32>>,
+    );
+
+    fn check_xfer(e: Env, from: Identifier, to: Identifier, amount: BigInt) -> u32;
+
+    fn set_max(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        max: Option<BigInt>,
+    );
+
+    fn set_max_id(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        max: Option<BigInt>,
+    );
+
+    fn set_max_ex(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        exempt: bool,
+    );
+
+    fn max_bal(e: Env, id: Identifier) -> Option<BigInt>;
+
+    fn set_limit(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        limit: Option<VelocityLimit>,
+    );
+
+    fn remaining(e: Env, id: Identifier) -> Option<BigInt>;
+
+    fn set_tlock(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        timelock: Option<Timelock>,
+    );
+
+    fn queue(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        action: Action,
+    ) -> u32;
+
+    fn execute(e: Env, admin: Signature, nonce: BigInt, valid_until: Option<u32>, id: u32);
+
+    fn cancel(e: Env, admin: Signature, nonce: BigInt, valid_until: Option<u32>, id: u32);
+
+    fn queued(e: Env) -> Vec<QueuedAction>;
+}
+
+// Nonces are `lane * 2^64 + sequence`. Each lane is an independent sequence,
+// so a signer can have operations in flight in several lanes at once. Lane 0
+// is stored under `DataKey::Nonce` and holds the nonces used before lanes.
+struct WrappedAuth(Signature, u64);
+
+impl WrappedAuth {
+    fn new(e: &Env, signature: Signature, nonce: &BigInt) -> Self {
+        WrappedAuth(signature, nonce_lane(e, nonce))
+    }
+}
+
+fn lane_size(e: &Env) -> BigInt {
+    BigInt::from_u64(e, u64::MAX) + BigInt::from_u32(e, 1)
+}
+
+fn nonce_lane(e: &Env, nonce: &BigInt) -> u64 {
+    if *nonce < BigInt::zero(e) || *nonce >= lane_size(e) * lane_size(e) {
+        panic!("nonce out of range");
+    }
+    (nonce.clone() / lane_size(e)).to_u64()
+}
+
+fn nonce_key(id: Identifier, lane: u64) -> DataKey {
+    if lane == 0 {
+        DataKey::Nonce(id)
+    } else {
+        DataKey::LaneNonce(NonceKey { id, lane })
+    }
+}
+
+// A lane's sequence is stored on its own, and never reaches `lane_size`: the
+// last slot, `u64::MAX`, marks the lane as used up.
+fn read_lane_sequence(e: &Env, id: Identifier, lane: u64) -> BigInt {
+    let key = nonce_key(id, lane);
+    if let Some(sequence) = e.contract_data().get(key) {
+        sequence.unwrap()
+    } else {
+        BigInt::zero(e)
+    }
+}
+
+fn write_lane_sequence(e: &Env, id: Identifier, lane: u64, sequence: BigInt) {
+    let key = nonce_key(id, lane);
+    e.contract_data().set(key, sequence);
 }
 
-struct WrappedAuth(Signature);
+fn read_lane_nonce(e: &Env, id: Identifier, lane: u64) -> BigInt {
+    BigInt::from_u64(e, lane) * lane_size(e) + read_lane_sequence(e, id, lane)
+}
 
 impl NonceAuth for WrappedAuth {
     fn read_nonce(e: &Env, id: Identifier) -> BigInt {
-        let key = DataKey::Nonce(id);
-        if let Some(nonce) = e.contract_data().get(key) {
-            nonce.unwrap()
-        } else {
-            BigInt::zero(e)
-        }
+        read_lane_nonce(e, id, 0)
     }
 
     fn read_and_increment_nonce(&self, e: &Env, id: Identifier) -> BigInt {
-        let key = DataKey::Nonce(id.clone());
-        let nonce = Self::read_nonce(e, id);
-        e.contract_data()
-            .set(key, nonce.clone() + BigInt::from_u32(e, 1));
-        nonce
+        let sequence = read_lane_sequence(e, id.clone(), self.1);
+        if sequence == BigInt::from_u64(e, u64::MAX) {
+            panic!("nonce lane exhausted");
+        }
+        write_lane_sequence(e, id, self.1, sequence.clone() + BigInt::from_u32(e, 1));
+        BigInt::from_u64(e, self.1) * lane_size(e) + sequence
     }
 
     fn signature(&self) -> &Signature {
@@ -76,6 +382,90 @@ impl NonceAuth for WrappedAuth {
     }
 }
 
+fn check_valid_until(e: &Env, valid_until: Option<u32>) {
+    if let Some(valid_until) = valid_until {
+        if e.ledger().sequence() > valid_until {
+            panic!("signature expired");
+        }
+    }
+}
+
+fn xfer_balance(e: &Env, from: Identifier, to: Identifier, amount: BigInt) {
+    enforce_transfer(e, from.clone(), to.clone(), amount.clone());
+    let fee = compute_fee(e, from.clone(), to.clone(), amount.clone());
+    let spent = spend_balance(e, from, amount.clone());
+    let mut received = receive_balance(e, to, amount - fee.clone());
+    if fee > BigInt::zero(e) {
+        received = received + receive_balance(e, read_fee_config(e).unwrap().collector, fee);
+    }
+    burn_dust(e, spent, received);
+}
+
+// Debits round up and credits round down, so a transfer can debit a share
+// more than it credits. That share is taken out of the supply, which keeps the
+// supply equal to the sum of all balances.
+fn burn_dust(e: &Env, spent: BigInt, received: BigInt) {
+    decrease_supply(e, spent - received);
+}
+
+fn mint_balance(e: &Env, admin: Identifier, to: Identifier, amount: BigInt) {
+    check_not_wrapped(e);
+    enforce_transfer(e, admin, to.clone(), amount.clone());
+    let shares = receive_balance(e, to, amount);
+    increase_supply(e, shares);
+}
+
+fn burn_balance(e: &Env, from: Identifier, amount: BigInt) {
+    check_not_wrapped(e);
+    let shares = spend_balance(e, from, amount);
+    decrease_supply(e, shares);
+}
+
+fn update_index(e: &Env, index: BigInt) {
+    check_not_wrapped(e);
+    write_index(e, index);
+}
+
+fn update_rate(e: &Env, rate: BigInt) {
+    check_not_wrapped(e);
+    write_rate(e, rate);
+}
+
+fn update_metadata(e: &Env, admin: Identifier, name: Bytes, symbol: Bytes) {
+    check_name(&name);
+    check_symbol(&symbol);
+    write_name(e, name.clone());
+    write_symbol(e, symbol.clone());
+    event::set_meta(e, admin, name, symbol);
+}
+
+fn update_field(e: &Env, admin: Identifier, field: MetaField, value: Bytes) {
+    write_field(e, field, value.clone());
+    event::set_field(e, admin, field, value);
+}
+
+fn apply_action(e: &Env, admin: Identifier, action: Action) {
+    match action {
+        Action::Mint(mint) => mint_balance(e, admin, mint.to, mint.amount),
+        Action::SetAdmin(new_admin) => write_administrator(e, new_admin),
+        Action::SetMeta(meta) => update_metadata(e, admin, meta.name, meta.symbol),
+        Action::SetField(field) => update_field(e, admin, field.field, field.value),
+        Action::SetFee(config) => write_fee_config(e, config),
+        Action::SetExempt(exempt) => write_fee_exempt(e, exempt.id, exempt.exempt),
+        Action::SetOracle(oracle) => write_oracle(e, oracle),
+        Action::Rebase(index) => update_index(e, index),
+        Action::SetRate(rate) => update_rate(e, rate),
+        Action::SetTlock(timelock) => write_timelock(e, Some(timelock)),
+        Action::Burn(burn) => burn_balance(e, burn.from, burn.amount),
+        Action::SetComply(compliance) => write_compliance(e, compliance),
+        Action::SetMax(max) => write_max_balance(e, max),
+        Action::SetMaxId(max) => write_account_max(e, max.id, max.max),
+        Action::SetMaxEx(exempt) => write_max_exempt(e, exempt.id, exempt.exempt),
+        Action::SetLimit(limit) => write_velocity_limit(e, limit.id, limit.limit),
+        Action::Unlock => write_timelock(e, None),
+    }
+}
+
 pub struct Token;
 
 #[cfg_attr(feature = "export", contractimpl)]
@@ -86,28 +476,45 @@ impl TokenTrait for Token {
             panic!("already initialized")
         }
         write_administrator(&e, admin);
+        write_version(&e, CURRENT_VERSION);
 
-        write_decimal(&e, u8::try_from(decimal).expect("Decimal must fit in a u8"));
+        check_name(&name);
+        check_symbol(&symbol);
+        write_decimal(&e, check_decimal(decimal));
         write_name(&e, name);
         write_symbol(&e, symbol);
     }
 
-    fn nonce(e: Env, id: Identifier) -> BigInt {
-        <WrappedAuth as NonceAuth>::read_nonce(&e, id)
+    fn init_wrap(e: Env, admin: Identifier, underlying: BytesN<32>, name: Bytes, symbol: Bytes) {
+        let decimal = TokenClient::new(&e, &underlying).decimals();
+        Self::initialize(e.clone(), admin, decimal, name, symbol);
+        write_underlying(&e, underlying);
+    }
+
+    fn nonce(e: Env, id: Identifier, lane: Option<u64>) -> BigInt {
+        read_lane_nonce(&e, id, lane.unwrap_or(0))
     }
 
     fn allowance(e: Env, from: Identifier, spender: Identifier) -> BigInt {
         read_allowance(&e, from, spender)
     }
 
-    fn approve(e: Env, from: Signature, nonce: BigInt, spender: Identifier, amount: BigInt) {
+    fn approve(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        spender: Identifier,
+        amount: BigInt,
+    ) {
         let from_id = from.get_identifier(&e);
+        check_valid_until(&e, valid_until);
         check_auth(
             &e,
-            &WrappedAuth(from),
+            &WrappedAuth::new(&e, from, &nonce),
             nonce.clone(),
             symbol!("approve"),
-            (&from_id, nonce, &spender, &amount).into_val(&e),
+            (&from_id, nonce, valid_until, &spender, &amount).into_val(&e),
         );
         write_allowance(&e, from_id, spender, amount);
     }
@@ -116,112 +523,170 @@ impl TokenTrait for Token {
         read_balance(&e, id)
     }
 
-    fn is_frozen(e: Env, id: Identifier) -> bool {
-        read_state(&e, id)
+    fn is_frozen(e: Env, id: Identifier) -> FreezeMode {
+        read_freeze_mode(&e, id)
     }
 
-    fn xfer(e: Env, from: Signature, nonce: BigInt, to: Identifier, amount: BigInt) {
+    fn xfer(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        to: Identifier,
+        amount: BigInt,
+    ) {
         let from_id = from.get_identifier(&e);
+        check_valid_until(&e, valid_until);
         check_auth(
             &e,
-            &WrappedAuth(from),
+            &WrappedAuth::new(&e, from, &nonce),
             nonce.clone(),
             symbol!("xfer"),
-            (&from_id, nonce, &to, &amount).into_val(&e),
+            (&from_id, nonce, valid_until, &to, &amount).into_val(&e),
         );
-        spend_balance(&e, from_id, amount.clone());
-        receive_balance(&e, to, amount);
+        xfer_balance(&e, from_id, to, amount);
     }
 
     fn xfer_from(
         e: Env,
         spender: Signature,
         nonce: BigInt,
+        valid_until: Option<u32>,
         from: Identifier,
         to: Identifier,
         amount: BigInt,
     ) {
         let spender_id = spender.get_identifier(&e);
+        check_valid_until(&e, valid_until);
         check_auth(
             &e,
-            &WrappedAuth(spender),
+            &WrappedAuth::new(&e, spender, &nonce),
             nonce.clone(),
             symbol!("xfer_from"),
-            (&spender_id, nonce, &from, &to, &amount).into_val(&e),
+            (&spender_id, nonce, valid_until, &from, &to, &amount).into_val(&e),
         );
         spend_allowance(&e, from.clone(), spender_id, amount.clone());
-        spend_balance(&e, from, amount.clone());
-        receive_balance(&e, to, amount);
+        xfer_balance(&e, from, to, amount);
     }
 
-    fn burn(e: Env, admin: Signature, nonce: BigInt, from: Identifier, amount: BigInt) {
+    fn burn(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        from: Identifier,
+        amount: BigInt,
+    ) {
         check_admin(&e, &admin);
         let admin_id = admin.get_identifier(&e);
 
+        check_valid_until(&e, valid_until);
         check_auth(
             &e,
-            &WrappedAuth(admin),
+            &WrappedAuth::new(&e, admin, &nonce),
             nonce.clone(),
             symbol!("burn"),
-            (admin_id, nonce, &from, &amount).into_val(&e),
+            (admin_id, nonce, valid_until, &from, &amount).into_val(&e),
         );
-        spend_balance(&e, from, amount);
+        check_unlocked(&e);
+        burn_balance(&e, from, amount);
     }
 
-    fn freeze(e: Env, admin: Signature, nonce: BigInt, id: Identifier) {
+    fn freeze(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        mode: FreezeMode,
+        reason: u32,
+        reference: Option<Bytes>,
+    ) {
         check_admin(&e, &admin);
         let admin_id = admin.get_identifier(&e);
 
+        check_valid_until(&e, valid_until);
         check_auth(
             &e,
-            &WrappedAuth(admin),
+            &WrappedAuth::new(&e, admin, &nonce),
             nonce.clone(),
             symbol!("freeze"),
-            (admin_id, nonce, &id).into_val(&e),
+            (&admin_id, nonce, valid_until, &id, mode, reason, &reference).into_val(&e),
         );
-        write_state(&e, id, true);
+        write_freeze_mode(&e, id.clone(), mode);
+        let record = write_freeze_record(&e, id.clone(), admin_id, mode, reason, reference);
+        event::freeze(&e, id, record);
     }
 
-    fn mint(e: Env, admin: Signature, nonce: BigInt, to: Identifier, amount: BigInt) {
+    fn mint(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        to: Identifier,
+        amount: BigInt,
+    ) {
         check_admin(&e, &admin);
         let admin_id = admin.get_identifier(&e);
 
+        check_valid_until(&e, valid_until);
         check_auth(
             &e,
-            &WrappedAuth(admin),
+            &WrappedAuth::new(&e, admin, &nonce),
             nonce.clone(),
             symbol!("mint"),
-            (admin_id, nonce, &to, &amount).into_val(&e),
+            (&admin_id, nonce, valid_until, &to, &amount).into_val(&e),
         );
-        receive_balance(&e, to, amount);
+        use_mint_allowance(&e, &amount);
+        mint_balance(&e, admin_id, to, amount);
     }
 
-    fn set_admin(e: Env, admin: Signature, nonce: BigInt, new_admin: Identifier) {
+    fn set_admin(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        new_admin: Identifier,
+    ) {
         check_admin(&e, &admin);
         let admin_id = admin.get_identifier(&e);
 
+        check_valid_until(&e, valid_until);
         check_auth(
             &e,
-            &WrappedAuth(admin),
+            &WrappedAuth::new(&e, admin, &nonce),
             nonce.clone(),
             symbol!("set_admin"),
-            (admin_id, nonce, &new_admin).into_val(&e),
+            (admin_id, nonce, valid_until, &new_admin).into_val(&e),
         );
+        check_unlocked(&e);
         write_administrator(&e, new_admin);
     }
 
-    fn unfreeze(e: Env, admin: Signature, nonce: BigInt, id: Identifier) {
+    fn unfreeze(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        reason: u32,
+        reference: Option<Bytes>,
+    ) {
         check_admin(&e, &admin);
         let admin_id = admin.get_identifier(&e);
 
+        check_valid_until(&e, valid_until);
         check_auth(
             &e,
-            &WrappedAuth(admin),
+            &WrappedAuth::new(&e, admin, &nonce),
             nonce.clone(),
             symbol!("unfreeze"),
-            (admin_id, nonce, &id).into_val(&e),
+            (&admin_id, nonce, valid_until, &id, reason, &reference).into_val(&e),
         );
-        write_state(&e, id, false);
+        write_freeze_mode(&e, id.clone(), FreezeMode::Off);
+        let record =
+            write_freeze_record(&e, id.clone(), admin_id, FreezeMode::Off, reason, reference);
+        event::unfreeze(&e, id, record);
     }
 
     fn decimals(e: Env) -> u32 {
@@ -235,4 +700,612 @@ impl TokenTrait for Token {
     fn symbol(e: Env) -> Bytes {
         read_symbol(&e)
     }
+
+    fn delegate(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        delegatee: Identifier,
+    ) {
+        let from_id = from.get_identifier(&e);
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, from, &nonce),
+            nonce.clone(),
+            symbol!("delegate"),
+            (&from_id, nonce, valid_until, &delegatee).into_val(&e),
+        );
+        let shares = read_shares(&e, from_id.clone());
+        sub_delegated_votes(&e, from_id.clone(), shares.clone());
+        write_delegate(&e, from_id.clone(), delegatee);
+        add_delegated_votes(&e, from_id, shares);
+    }
+
+    fn votes(e: Env, id: Identifier) -> BigInt {
+        read_votes(&e, id)
+    }
+
+    fn votes_at(e: Env, id: Identifier, ledger: u32) -> BigInt {
+        read_votes_at(&e, id, ledger)
+    }
+
+    fn xfer_relay(
+        e: Env,
+        from: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        to: Identifier,
+        amount: BigInt,
+        relayer: Identifier,
+        fee: BigInt,
+    ) {
+        let from_id = from.get_identifier(&e);
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, from, &nonce),
+            nonce.clone(),
+            symbol!("xfer_relay"),
+            (&from_id, nonce, valid_until, &to, &amount, &relayer, &fee).into_val(&e),
+        );
+        if amount < BigInt::zero(&e) {
+            panic!("amount must not be negative");
+        }
+        if fee < BigInt::zero(&e) {
+            panic!("fee must not be negative");
+        }
+        xfer_balance(&e, from_id.clone(), to, amount);
+        enforce_transfer(&e, from_id.clone(), relayer.clone(), fee.clone());
+        let spent = spend_balance(&e, from_id, fee.clone());
+        let received = receive_balance(&e, relayer, fee);
+        burn_dust(&e, spent, received);
+    }
+
+    fn set_fee(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        fee_bps: u32,
+        min_fee: BigInt,
+        max_fee: BigInt,
+        collector: Identifier,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_fee"),
+            (
+                admin_id,
+                nonce,
+                valid_until,
+                fee_bps,
+                &min_fee,
+                &max_fee,
+                &collector,
+            )
+                .into_val(&e),
+        );
+        check_unlocked(&e);
+        write_fee_config(
+            &e,
+            FeeConfig {
+                fee_bps,
+                min_fee,
+                max_fee,
+                collector,
+            },
+        );
+    }
+
+    fn set_exempt(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        exempt: bool,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_exempt"),
+            (admin_id, nonce, valid_until, &id, exempt).into_val(&e),
+        );
+        check_unlocked(&e);
+        write_fee_exempt(&e, id, exempt);
+    }
+
+    fn quote_xfer(e: Env, from: Identifier, to: Identifier, amount: BigInt) -> XferQuote {
+        let fee = compute_fee(&e, from, to, amount.clone());
+        XferQuote {
+            net: amount - fee.clone(),
+            fee,
+        }
+    }
+
+    fn shares_of(e: Env, id: Identifier) -> BigInt {
+        read_shares(&e, id)
+    }
+
+    fn index(e: Env) -> BigInt {
+        read_index(&e)
+    }
+
+    fn set_oracle(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        oracle: Identifier,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_oracle"),
+            (admin_id, nonce, valid_until, &oracle).into_val(&e),
+        );
+        check_unlocked(&e);
+        write_oracle(&e, oracle);
+    }
+
+    fn rebase(e: Env, auth: Signature, nonce: BigInt, valid_until: Option<u32>, index: BigInt) {
+        let auth_id = auth.get_identifier(&e);
+        if auth_id != read_administrator(&e) && Some(auth_id.clone()) != read_oracle(&e) {
+            panic!("not authorized by admin or oracle")
+        }
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, auth, &nonce),
+            nonce.clone(),
+            symbol!("rebase"),
+            (&auth_id, nonce, valid_until, &index).into_val(&e),
+        );
+        // The oracle was installed by the admin, through the queue if a
+        // timelock is set, so only the admin's own rebases must be queued.
+        if auth_id == read_administrator(&e) {
+            check_unlocked(&e);
+        }
+        update_index(&e, index);
+    }
+
+    fn set_rate(e: Env, admin: Signature, nonce: BigInt, valid_until: Option<u32>, rate: BigInt) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_rate"),
+            (admin_id, nonce, valid_until, &rate).into_val(&e),
+        );
+        check_unlocked(&e);
+        update_rate(&e, rate);
+    }
+
+    fn accrued(e: Env, id: Identifier) -> BigInt {
+        read_accrued_interest(&e, id)
+    }
+
+    fn supply(e: Env) -> BigInt {
+        to_amount(&e, read_supply(&e))
+    }
+
+    fn deposit(e: Env, from: Signature, nonce: BigInt, valid_until: Option<u32>, amount: BigInt) {
+        let underlying = read_underlying(&e);
+        let from_id = from.get_identifier(&e);
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, from, &nonce),
+            nonce.clone(),
+            symbol!("deposit"),
+            (&from_id, nonce, valid_until, &amount).into_val(&e),
+        );
+        if amount <= BigInt::zero(&e) {
+            panic!("amount must be positive");
+        }
+        let client = TokenClient::new(&e, &underlying);
+        let this = Identifier::Contract(e.get_current_contract());
+        let before = client.balance(&this);
+        client.xfer_from(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &None,
+            &from_id,
+            &this,
+            &amount,
+        );
+        // The underlying token may charge a fee on the transfer, so only what
+        // actually arrived is credited.
+        let received = client.balance(&this) - before;
+        if received <= BigInt::zero(&e) {
+            panic!("nothing received from the underlying token");
+        }
+        enforce_transfer(&e, this, from_id.clone(), received.clone());
+        let shares = receive_balance(&e, from_id, received);
+        increase_supply(&e, shares);
+    }
+
+    fn withdraw(e: Env, from: Signature, nonce: BigInt, valid_until: Option<u32>, amount: BigInt) {
+        let underlying = read_underlying(&e);
+        let from_id = from.get_identifier(&e);
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, from, &nonce),
+            nonce.clone(),
+            symbol!("withdraw"),
+            (&from_id, nonce, valid_until, &amount).into_val(&e),
+        );
+        if amount <= BigInt::zero(&e) {
+            panic!("amount must be positive");
+        }
+        let shares = spend_balance(&e, from_id.clone(), amount.clone());
+        decrease_supply(&e, shares);
+        TokenClient::new(&e, &underlying).xfer(
+            &Signature::Invoker,
+            &BigInt::zero(&e),
+            &None,
+            &from_id,
+            &amount,
+        );
+    }
+
+    fn set_meta(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        name: Bytes,
+        symbol: Bytes,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_meta"),
+            (&admin_id, nonce, valid_until, &name, &symbol).into_val(&e),
+        );
+        check_unlocked(&e);
+        update_metadata(&e, admin_id, name, symbol);
+    }
+
+    fn set_field(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        field: MetaField,
+        value: Bytes,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_field"),
+            (&admin_id, nonce, valid_until, field, &value).into_val(&e),
+        );
+        check_unlocked(&e);
+        update_field(&e, admin_id, field, value);
+    }
+
+    fn metadata(e: Env) -> TokenMetadata {
+        read_metadata(&e)
+    }
+
+    fn version(e: Env) -> u32 {
+        read_version(&e)
+    }
+
+    fn migrate(e: Env, admin: Signature, nonce: BigInt, valid_until: Option<u32>) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("migrate"),
+            (admin_id, nonce, valid_until).into_val(&e),
+        );
+        migrate_storage(&e);
+    }
+
+    fn invalidate(e: Env, from: Signature, nonce: BigInt, valid_until: Option<u32>, up_to: BigInt) {
+        let from_id = from.get_identifier(&e);
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, from, &nonce),
+            nonce.clone(),
+            symbol!("invalidate"),
+            (&from_id, nonce, valid_until, &up_to).into_val(&e),
+        );
+        // Every nonce below `up_to` in its lane becomes unusable.
+        let lane = nonce_lane(&e, &up_to);
+        let sequence = up_to.clone() % lane_size(&e);
+        if sequence == BigInt::from_u64(&e, u64::MAX) {
+            panic!("nonce out of range");
+        }
+        let next = read_lane_nonce(&e, from_id.clone(), lane);
+        if up_to <= next {
+            panic!("nonces already used");
+        }
+        write_lane_sequence(&e, from_id, lane, sequence);
+    }
+
+    fn holder_cnt(e: Env) -> u32 {
+        read_holder_count(&e)
+    }
+
+    fn holders(e: Env, start: u32, limit: u32) -> Vec<Identifier> {
+        read_holders(&e, start, limit)
+    }
+
+    fn allowances(e: Env, from: Identifier, start: u32, limit: u32) -> Vec<Approval> {
+        read_approvals(&e, from, start, limit)
+    }
+
+    fn revoke_all(e: Env, from: Signature, nonce: BigInt, valid_until: Option<u32>) {
+        let from_id = from.get_identifier(&e);
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, from, &nonce),
+            nonce.clone(),
+            symbol!("revoke_all"),
+            (&from_id, nonce, valid_until).into_val(&e),
+        );
+        revoke_allowances(&e, from_id);
+    }
+
+    fn freeze_rec(e: Env, id: Identifier) -> Option<FreezeRecord> {
+        read_freeze_record(&e, id)
+    }
+
+    fn freeze_log(e: Env, id: Identifier) -> Vec<FreezeRecord> {
+        read_freeze_log(&e, id)
+    }
+
+    fn set_comply(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        compliance: Option<BytesN<32>>,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_comply"),
+            (admin_id, nonce, valid_until, &compliance).into_val(&e),
+        );
+        check_unlocked(&e);
+        write_compliance(&e, compliance);
+    }
+
+    fn check_xfer(e: Env, from: Identifier, to: Identifier, amount: BigInt) -> u32 {
+        check_transfer(&e, from, to, amount)
+    }
+
+    fn set_max(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        max: Option<BigInt>,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_max"),
+            (admin_id, nonce, valid_until, &max).into_val(&e),
+        );
+        check_unlocked(&e);
+        write_max_balance(&e, max);
+    }
+
+    fn set_max_id(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        max: Option<BigInt>,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_max_id"),
+            (admin_id, nonce, valid_until, &id, &max).into_val(&e),
+        );
+        check_unlocked(&e);
+        write_account_max(&e, id, max);
+    }
+
+    fn set_max_ex(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        exempt: bool,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_max_ex"),
+            (admin_id, nonce, valid_until, &id, exempt).into_val(&e),
+        );
+        check_unlocked(&e);
+        write_max_exempt(&e, id, exempt);
+    }
+
+    fn max_bal(e: Env, id: Identifier) -> Option<BigInt> {
+        read_limit(&e, id)
+    }
+
+    fn set_limit(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        id: Identifier,
+        limit: Option<VelocityLimit>,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_limit"),
+            (admin_id, nonce, valid_until, &id, &limit).into_val(&e),
+        );
+        check_unlocked(&e);
+        write_velocity_limit(&e, id, limit);
+    }
+
+    fn remaining(e: Env, id: Identifier) -> Option<BigInt> {
+        read_remaining(&e, id)
+    }
+
+    fn set_tlock(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        timelock: Option<Timelock>,
+    ) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("set_tlock"),
+            (admin_id, nonce, valid_until, &timelock).into_val(&e),
+        );
+        check_unlocked(&e);
+        write_timelock(&e, timelock);
+    }
+
+    fn queue(
+        e: Env,
+        admin: Signature,
+        nonce: BigInt,
+        valid_until: Option<u32>,
+        action: Action,
+    ) -> u32 {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("queue"),
+            (&admin_id, nonce, valid_until, &action).into_val(&e),
+        );
+        let queued = enqueue(&e, action);
+        let id = queued.id;
+        event::queue(&e, admin_id, queued);
+        id
+    }
+
+    fn execute(e: Env, admin: Signature, nonce: BigInt, valid_until: Option<u32>, id: u32) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("execute"),
+            (&admin_id, nonce, valid_until, id).into_val(&e),
+        );
+        let queued = dequeue(&e, id);
+        if queued.eta > e.ledger().timestamp() {
+            panic!("action not ready");
+        }
+        event::execute(&e, admin_id.clone(), id);
+        apply_action(&e, admin_id, queued.action);
+    }
+
+    fn cancel(e: Env, admin: Signature, nonce: BigInt, valid_until: Option<u32>, id: u32) {
+        check_admin(&e, &admin);
+        let admin_id = admin.get_identifier(&e);
+
+        check_valid_until(&e, valid_until);
+        check_auth(
+            &e,
+            &WrappedAuth::new(&e, admin, &nonce),
+            nonce.clone(),
+            symbol!("cancel"),
+            (&admin_id, nonce, valid_until, id).into_val(&e),
+        );
+        dequeue(&e, id);
+        event::cancel(&e, admin_id, id);
+    }
+
+    fn queued(e: Env) -> Vec<QueuedAction> {
+        read_queue(&e)
+    }
 }
@@ -0,0 +1,40 @@
+use crate::freeze::FreezeRecord;
+use crate::metadata::MetaField;
+use crate::timelock::QueuedAction;
+use soroban_auth::Identifier;
+use soroban_sdk::{symbol, Bytes, Env};
+
+pub(crate) fn set_meta(e: &Env, admin: Identifier, name: Bytes, symbol: Bytes) {
+    let topics = (symbol!("set_meta"), admin);
+    e.events().publish(topics, (name, symbol));
+}
+
+pub(crate) fn set_field(e: &Env, admin: Identifier, field: MetaField, value: Bytes) {
+    let topics = (symbol!("set_field"), admin, field);
+    e.events().publish(topics, value);
+}
+
+pub(crate) fn freeze(e: &Env, id: Identifier, record: FreezeRecord) {
+    let topics = (symbol!("freeze"), record.admin.clone(), id);
+    e.events().publish(topics, record);
+}
+
+pub(crate) fn unfreeze(e: &Env, id: Identifier, record: FreezeRecord) {
+    let topics = (symbol!("unfreeze"), record.admin.clone(), id);
+    e.events().publish(topics, record);
+}
+
+pub(crate) fn queue(e: &Env, admin: Identifier, queued: QueuedAction) {
+    let topics = (symbol!("queue"), admin, queued.id);
+    e.events().publish(topics, queued);
+}
+
+pub(crate) fn execute(e: &Env, admin: Identifier, id: u32) {
+    let topics = (symbol!("execute"), admin, id);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn cancel(e: &Env, admin: Identifier, id: u32) {
+    let topics = (symbol!("cancel"), admin, id);
+    e.events().publish(topics, ());
+}
@@ -0,0 +1,79 @@
+use crate::storage_types::{DataKey, FeeConfig};
+use soroban_auth::Identifier;
+use soroban_sdk::{contracttype, BigInt, Env};
+
+const MAX_BPS: u32 = 10_000;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct XferQuote {
+    pub net: BigInt,
+    pub fee: BigInt,
+}
+
+pub fn read_fee_config(e: &Env) -> Option<FeeConfig> {
+    let key = DataKey::FeeConfig;
+    if let Some(config) = e.contract_data().get(key) {
+        Some(config.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn write_fee_config(e: &Env, config: FeeConfig) {
+    if config.fee_bps > MAX_BPS {
+        panic!("fee_bps must not exceed 10000");
+    }
+    if config.min_fee < BigInt::zero(e) {
+        panic!("min_fee must not be negative");
+    }
+    if config.max_fee < config.min_fee {
+        panic!("max_fee must not be less than min_fee");
+    }
+    let key = DataKey::FeeConfig;
+    e.contract_data().set(key, config);
+}
+
+pub fn is_fee_exempt(e: &Env, id: Identifier) -> bool {
+    let key = DataKey::FeeExempt(id);
+    if let Some(exempt) = e.contract_data().get(key) {
+        exempt.unwrap()
+    } else {
+        false
+    }
+}
+
+pub fn write_fee_exempt(e: &Env, id: Identifier, exempt: bool) {
+    let key = DataKey::FeeExempt(id);
+    e.contract_data().set(key, exempt);
+}
+
+/// Returns the fee charged on a transfer of `amount` from `from` to `to`. The
+/// fee is `fee_bps` of the amount clamped to `[min_fee, max_fee]`, and never
+/// more than the amount itself.
+pub fn compute_fee(e: &Env, from: Identifier, to: Identifier, amount: BigInt) -> BigInt {
+    if amount < BigInt::zero(e) {
+        panic!("amount must not be negative");
+    }
+    let config = if let Some(config) = read_fee_config(e) {
+        config
+    } else {
+        return BigInt::zero(e);
+    };
+    if is_fee_exempt(e, from) || is_fee_exempt(e, to) {
+        return BigInt::zero(e);
+    }
+
+    let mut fee =
+        amount.clone() * BigInt::from_u32(e, config.fee_bps) / BigInt::from_u32(e, MAX_BPS);
+    if fee < config.min_fee {
+        fee = config.min_fee;
+    }
+    if fee > config.max_fee {
+        fee = config.max_fee;
+    }
+    if fee > amount {
+        fee = amount;
+    }
+    fee
+}
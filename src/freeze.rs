@@ -0,0 +1,96 @@
+use crate::storage_types::{DataKey, FreezeKey};
+use soroban_auth::Identifier;
+use soroban_sdk::{contracttype, Bytes, Env, Vec};
+
+/// Which transfers a frozen account is blocked from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum FreezeMode {
+    Off,
+    Outbound,
+    Inbound,
+    Full,
+}
+
+impl FreezeMode {
+    pub fn blocks_outbound(&self) -> bool {
+        matches!(self, FreezeMode::Outbound | FreezeMode::Full)
+    }
+
+    pub fn blocks_inbound(&self) -> bool {
+        matches!(self, FreezeMode::Inbound | FreezeMode::Full)
+    }
+}
+
+/// Why, when and by whom an account was last frozen or unfrozen.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FreezeRecord {
+    pub mode: FreezeMode,
+    pub reason: u32,
+    pub reference: Option<Bytes>,
+    pub ledger: u32,
+    pub timestamp: u64,
+    pub admin: Identifier,
+}
+
+// An account's records are stored one per entry, under `DataKey::FreezeRec`
+// with indexes `0..count`, so that a long history doesn't make each new record
+// more expensive to write.
+
+fn read_freeze_count(e: &Env, id: Identifier) -> u32 {
+    let key = DataKey::FreezeCnt(id);
+    if let Some(count) = e.contract_data().get(key) {
+        count.unwrap()
+    } else {
+        0
+    }
+}
+
+fn read_freeze_entry(e: &Env, id: Identifier, index: u32) -> FreezeRecord {
+    let key = DataKey::FreezeRec(FreezeKey { id, index });
+    e.contract_data().get_unchecked(key).unwrap()
+}
+
+pub fn read_freeze_log(e: &Env, id: Identifier) -> Vec<FreezeRecord> {
+    let mut log = Vec::new(e);
+    for index in 0..read_freeze_count(e, id.clone()) {
+        log.push_back(read_freeze_entry(e, id.clone(), index));
+    }
+    log
+}
+
+pub fn read_freeze_record(e: &Env, id: Identifier) -> Option<FreezeRecord> {
+    let count = read_freeze_count(e, id.clone());
+    if count == 0 {
+        return None;
+    }
+    Some(read_freeze_entry(e, id, count - 1))
+}
+
+pub fn write_freeze_record(
+    e: &Env,
+    id: Identifier,
+    admin: Identifier,
+    mode: FreezeMode,
+    reason: u32,
+    reference: Option<Bytes>,
+) -> FreezeRecord {
+    let record = FreezeRecord {
+        mode,
+        reason,
+        reference,
+        ledger: e.ledger().sequence(),
+        timestamp: e.ledger().timestamp(),
+        admin,
+    };
+    let count = read_freeze_count(e, id.clone());
+    let key = DataKey::FreezeRec(FreezeKey {
+        id: id.clone(),
+        index: count,
+    });
+    e.contract_data().set(key, record.clone());
+    let key = DataKey::FreezeCnt(id);
+    e.contract_data().set(key, count + 1);
+    record
+}
@@ -0,0 +1,75 @@
+use crate::storage_types::DataKey;
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env, Vec};
+
+// Holders are kept in a dense list, `DataKey::Holder(0..count)`, with each
+// holder's position stored under `DataKey::HolderIdx` so that removal can
+// move the last holder into the vacated slot.
+
+pub fn read_holder_count(e: &Env) -> u32 {
+    let key = DataKey::Holders;
+    if let Some(count) = e.contract_data().get(key) {
+        count.unwrap()
+    } else {
+        0
+    }
+}
+
+fn write_holder_count(e: &Env, count: u32) {
+    let key = DataKey::Holders;
+    e.contract_data().set(key, count);
+}
+
+fn read_holder(e: &Env, index: u32) -> Identifier {
+    let key = DataKey::Holder(index);
+    e.contract_data().get_unchecked(key).unwrap()
+}
+
+fn read_holder_index(e: &Env, id: Identifier) -> Option<u32> {
+    let key = DataKey::HolderIdx(id);
+    if let Some(index) = e.contract_data().get(key) {
+        Some(index.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn read_holders(e: &Env, start: u32, limit: u32) -> Vec<Identifier> {
+    let end = read_holder_count(e).min(start.saturating_add(limit));
+    let mut holders = Vec::new(e);
+    for index in start..end {
+        holders.push_back(read_holder(e, index));
+    }
+    holders
+}
+
+fn add_holder(e: &Env, id: Identifier) {
+    let count = read_holder_count(e);
+    e.contract_data().set(DataKey::Holder(count), id.clone());
+    e.contract_data().set(DataKey::HolderIdx(id), count);
+    write_holder_count(e, count + 1);
+}
+
+fn remove_holder(e: &Env, id: Identifier, index: u32) {
+    let last = read_holder_count(e) - 1;
+    if index != last {
+        let moved = read_holder(e, last);
+        e.contract_data().set(DataKey::Holder(index), moved.clone());
+        e.contract_data().set(DataKey::HolderIdx(moved), index);
+    }
+    e.contract_data().remove(DataKey::Holder(last));
+    e.contract_data().remove(DataKey::HolderIdx(id));
+    write_holder_count(e, last);
+}
+
+/// Keeps the holder index in step with a balance change. Accounts that held
+/// shares before the index existed are added the next time they are touched.
+pub fn update_holder(e: &Env, id: Identifier, shares: &BigInt) {
+    let index = read_holder_index(e, id.clone());
+    let holds = *shares != BigInt::zero(e);
+    match index {
+        None if holds => add_holder(e, id),
+        Some(index) if !holds => remove_holder(e, id, index),
+        _ => {}
+    }
+}
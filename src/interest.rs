@@ -0,0 +1,93 @@
+use crate::storage_types::{Accrual, DataKey};
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env};
+
+/// Scale of the interest index and of the per-second rate, so that a rate of
+/// `RATE_SCALE` is 100% per second.
+pub const RATE_SCALE: u64 = 1_000_000_000_000_000_000;
+
+pub fn read_rate(e: &Env) -> BigInt {
+    let key = DataKey::Rate;
+    if let Some(rate) = e.contract_data().get(key) {
+        rate.unwrap()
+    } else {
+        BigInt::zero(e)
+    }
+}
+
+pub fn write_rate(e: &Env, rate: BigInt) {
+    if rate < BigInt::zero(e) {
+        panic!("rate must not be negative");
+    }
+    // Settle the index at the old rate before switching to the new one.
+    let index = read_interest_index(e);
+    write_accrual(
+        e,
+        Accrual {
+            index,
+            updated: e.ledger().timestamp(),
+        },
+    );
+    let key = DataKey::Rate;
+    e.contract_data().set(key, rate);
+}
+
+fn read_accrual(e: &Env) -> Accrual {
+    let key = DataKey::Accrual;
+    if let Some(accrual) = e.contract_data().get(key) {
+        accrual.unwrap()
+    } else {
+        Accrual {
+            index: BigInt::from_u64(e, RATE_SCALE),
+            updated: e.ledger().timestamp(),
+        }
+    }
+}
+
+fn write_accrual(e: &Env, accrual: Accrual) {
+    let key = DataKey::Accrual;
+    e.contract_data().set(key, accrual);
+}
+
+pub fn read_interest_index(e: &Env) -> BigInt {
+    let accrual = read_accrual(e);
+    let elapsed = e.ledger().timestamp() - accrual.updated;
+    let scale = BigInt::from_u64(e, RATE_SCALE);
+    accrual.index * (scale.clone() + read_rate(e) * BigInt::from_u64(e, elapsed)) / scale
+}
+
+pub fn update_interest_index(e: &Env) -> BigInt {
+    let accrual = read_accrual(e);
+    if accrual.updated == e.ledger().timestamp() {
+        return accrual.index;
+    }
+    let index = read_interest_index(e);
+    write_accrual(
+        e,
+        Accrual {
+            index: index.clone(),
+            updated: e.ledger().timestamp(),
+        },
+    );
+    index
+}
+
+pub fn read_account_index(e: &Env, id: Identifier) -> BigInt {
+    let key = DataKey::AccrualIdx(id);
+    if let Some(index) = e.contract_data().get(key) {
+        index.unwrap()
+    } else {
+        BigInt::from_u64(e, RATE_SCALE)
+    }
+}
+
+pub fn write_account_index(e: &Env, id: Identifier, index: BigInt) {
+    let key = DataKey::AccrualIdx(id);
+    e.contract_data().set(key, index);
+}
+
+/// Returns the interest earned by `amount` while the index moved from `from`
+/// to `to`.
+pub fn interest_on(amount: BigInt, from: BigInt, to: BigInt) -> BigInt {
+    amount * (to - from.clone()) / from
+}
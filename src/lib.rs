@@ -1,15 +1,41 @@
 #![no_std]
 
-#[cfg(any(test, feature = "testutils"))]
+#[cfg(any(test, feature = "testutils", feature = "amount"))]
 #[macro_use]
 extern crate std;
 
 mod admin;
 mod allowance;
+pub mod amount;
+pub mod arbitrary;
 mod balance;
+mod compliance;
 mod contract;
+mod event;
+mod fee;
+mod freeze;
+mod holders;
+mod interest;
+mod max_balance;
 mod metadata;
+mod migrate;
+mod rebase;
 mod storage_types;
+mod supply;
 pub mod testutils;
+mod timelock;
+mod velocity;
+mod votes;
+mod wrapper;
 
+pub use crate::allowance::Approval;
 pub use crate::contract::TokenClient;
+pub use crate::fee::XferQuote;
+pub use crate::freeze::{FreezeMode, FreezeRecord};
+pub use crate::metadata::{MetaField, TokenMetadata};
+pub use crate::storage_types::FeeConfig;
+pub use crate::timelock::{
+    Action, BurnAction, ExemptAction, FieldAction, LimitAction, MaxAction, MetaAction, MintAction,
+    QueuedAction, Timelock,
+};
+pub use crate::velocity::VelocityLimit;
@@ -0,0 +1,80 @@
+use crate::storage_types::DataKey;
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env};
+
+pub fn read_max_balance(e: &Env) -> Option<BigInt> {
+    let key = DataKey::MaxBalance;
+    if let Some(max) = e.contract_data().get(key) {
+        Some(max.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn write_max_balance(e: &Env, max: Option<BigInt>) {
+    let key = DataKey::MaxBalance;
+    if let Some(max) = max {
+        if max < BigInt::zero(e) {
+            panic!("max balance must not be negative");
+        }
+        e.contract_data().set(key, max);
+    } else {
+        e.contract_data().remove(key);
+    }
+}
+
+fn read_account_max(e: &Env, id: Identifier) -> Option<BigInt> {
+    let key = DataKey::AccountMax(id);
+    if let Some(max) = e.contract_data().get(key) {
+        Some(max.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn write_account_max(e: &Env, id: Identifier, max: Option<BigInt>) {
+    let key = DataKey::AccountMax(id);
+    if let Some(max) = max {
+        if max < BigInt::zero(e) {
+            panic!("max balance must not be negative");
+        }
+        e.contract_data().set(key, max);
+    } else {
+        e.contract_data().remove(key);
+    }
+}
+
+fn is_max_exempt(e: &Env, id: Identifier) -> bool {
+    let key = DataKey::MaxExempt(id);
+    if let Some(exempt) = e.contract_data().get(key) {
+        exempt.unwrap()
+    } else {
+        false
+    }
+}
+
+pub fn write_max_exempt(e: &Env, id: Identifier, exempt: bool) {
+    let key = DataKey::MaxExempt(id);
+    e.contract_data().set(key, exempt);
+}
+
+/// Returns the most `id` may hold: its own override if it has one, otherwise
+/// the global maximum. Exempt accounts have no maximum.
+pub fn read_limit(e: &Env, id: Identifier) -> Option<BigInt> {
+    if is_max_exempt(e, id.clone()) {
+        return None;
+    }
+    if let Some(max) = read_account_max(e, id) {
+        Some(max)
+    } else {
+        read_max_balance(e)
+    }
+}
+
+pub fn check_max_balance(e: &Env, id: Identifier, balance: &BigInt) {
+    if let Some(max) = read_limit(e, id) {
+        if *balance > max {
+            panic!("balance exceeds maximum");
+        }
+    }
+}
@@ -1,5 +1,63 @@
 use crate::storage_types::DataKey;
-use soroban_sdk::{Bytes, Env};
+use soroban_sdk::{contracttype, Bytes, Env};
+
+const MAX_DECIMALS: u8 = 18;
+const MAX_NAME_LEN: u32 = 64;
+const MAX_SYMBOL_LEN: u32 = 12;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum MetaField {
+    IconUri,
+    HomeDomain,
+    Desc,
+    IssuerUrl,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TokenMetadata {
+    pub name: Bytes,
+    pub symbol: Bytes,
+    pub decimals: u32,
+    pub icon_uri: Bytes,
+    pub domain: Bytes,
+    pub desc: Bytes,
+    pub issuer_url: Bytes,
+}
+
+pub fn check_decimal(decimal: u32) -> u8 {
+    let decimal = u8::try_from(decimal).expect("Decimal must fit in a u8");
+    if decimal > MAX_DECIMALS {
+        panic!("decimal must not exceed 18");
+    }
+    decimal
+}
+
+pub fn check_name(name: &Bytes) {
+    if name.is_empty() {
+        panic!("name must not be empty");
+    }
+    if name.len() > MAX_NAME_LEN {
+        panic!("name must not exceed 64 bytes");
+    }
+    if name.iter().any(|b| !(0x20..=0x7e).contains(&b)) {
+        panic!("name must be printable ASCII");
+    }
+}
+
+pub fn check_symbol(symbol: &Bytes) {
+    if symbol.is_empty() {
+        panic!("symbol must not be empty");
+    }
+    if symbol.len() > MAX_SYMBOL_LEN {
+        panic!("symbol must not exceed 12 bytes");
+    }
+    // Unlike names, symbols can't contain spaces.
+    if symbol.iter().any(|b| !(0x21..=0x7e).contains(&b)) {
+        panic!("symbol must be printable ASCII without spaces");
+    }
+}
 
 pub fn read_decimal(e: &Env) -> u32 {
     let key = DataKey::Decimals;
@@ -30,3 +88,38 @@ pub fn write_symbol(e: &Env, d: Bytes) {
     let key = DataKey::Symbol;
     e.contract_data().set(key, d)
 }
+
+fn field_key(field: MetaField) -> DataKey {
+    match field {
+        MetaField::IconUri => DataKey::IconUri,
+        MetaField::HomeDomain => DataKey::HomeDomain,
+        MetaField::Desc => DataKey::Desc,
+        MetaField::IssuerUrl => DataKey::IssuerUrl,
+    }
+}
+
+pub fn read_field(e: &Env, field: MetaField) -> Bytes {
+    let key = field_key(field);
+    if let Some(value) = e.contract_data().get(key) {
+        value.unwrap()
+    } else {
+        Bytes::new(e)
+    }
+}
+
+pub fn write_field(e: &Env, field: MetaField, value: Bytes) {
+    let key = field_key(field);
+    e.contract_data().set(key, value)
+}
+
+pub fn read_metadata(e: &Env) -> TokenMetadata {
+    TokenMetadata {
+        name: read_name(e),
+        symbol: read_symbol(e),
+        decimals: read_decimal(e),
+        icon_uri: read_field(e, MetaField::IconUri),
+        domain: read_field(e, MetaField::HomeDomain),
+        desc: read_field(e, MetaField::Desc),
+        issuer_url: read_field(e, MetaField::IssuerUrl),
+    }
+}
@@ -0,0 +1,47 @@
+use crate::storage_types::DataKey;
+use soroban_sdk::Env;
+
+/// Version of the storage layout written by this code. Bump it, and add a
+/// step to `migrate`, whenever the encoding of a contract-wide entry changes.
+pub const CURRENT_VERSION: u32 = 1;
+
+// Entries keyed by account can't be enumerated by a migration step, so
+// changes to them don't bump the version. The code keeps reading the old key
+// and replaces it when the entry is next written, as `DataKey::State` is read
+// as a `FreezeMode`. Indexes added later, such as holders and spenders, start
+// empty and fill in as each account is next written.
+
+pub fn read_version(e: &Env) -> u32 {
+    let key = DataKey::Version;
+    if let Some(version) = e.contract_data().get(key) {
+        version.unwrap()
+    } else {
+        0
+    }
+}
+
+pub fn write_version(e: &Env, version: u32) {
+    let key = DataKey::Version;
+    e.contract_data().set(key, version);
+}
+
+pub fn migrate_storage(e: &Env) {
+    let mut version = read_version(e);
+    if version >= CURRENT_VERSION {
+        panic!("already at latest version");
+    }
+    while version < CURRENT_VERSION {
+        match version {
+            0 => migrate_v0(e),
+            _ => unreachable!(),
+        }
+        version += 1;
+        write_version(e, version);
+    }
+}
+
+// Version 0 is the layout written before versioning was introduced. None of
+// its contract-wide entries changed encoding in version 1, so this step only
+// records the version: balances are read as shares, which are worth one unit
+// each until the first rebase.
+fn migrate_v0(_e: &Env) {}
@@ -0,0 +1,56 @@
+use crate::storage_types::DataKey;
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env};
+
+/// The index at which one share is worth one unit of the token.
+pub const INDEX_SCALE: u32 = 1_000_000_000;
+
+pub fn read_index(e: &Env) -> BigInt {
+    let key = DataKey::Index;
+    if let Some(index) = e.contract_data().get(key) {
+        index.unwrap()
+    } else {
+        BigInt::from_u32(e, INDEX_SCALE)
+    }
+}
+
+pub fn write_index(e: &Env, index: BigInt) {
+    if index <= BigInt::zero(e) {
+        panic!("index must be positive");
+    }
+    let key = DataKey::Index;
+    e.contract_data().set(key, index);
+}
+
+pub fn read_oracle(e: &Env) -> Option<Identifier> {
+    let key = DataKey::Oracle;
+    if let Some(oracle) = e.contract_data().get(key) {
+        Some(oracle.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn write_oracle(e: &Env, id: Identifier) {
+    let key = DataKey::Oracle;
+    e.contract_data().set(key, id);
+}
+
+// Conversions between shares and amounts never round in the holder's favour:
+// amounts are rounded down, shares credited are rounded down, and shares
+// debited are rounded up. The difference between what a sender is debited and
+// what a recipient is credited is at most one share and is removed from the
+// supply.
+
+pub fn to_amount(e: &Env, shares: BigInt) -> BigInt {
+    shares * read_index(e) / BigInt::from_u32(e, INDEX_SCALE)
+}
+
+pub fn to_shares_down(e: &Env, amount: BigInt) -> BigInt {
+    amount * BigInt::from_u32(e, INDEX_SCALE) / read_index(e)
+}
+
+pub fn to_shares_up(e: &Env, amount: BigInt) -> BigInt {
+    let index = read_index(e);
+    (amount * BigInt::from_u32(e, INDEX_SCALE) + index.clone() - BigInt::from_u32(e, 1)) / index
+}
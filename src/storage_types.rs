@@ -1,5 +1,5 @@
 use soroban_auth::Identifier;
-use soroban_sdk::contracttype;
+use soroban_sdk::{contracttype, BigInt};
 
 #[derive(Clone)]
 #[contracttype]
@@ -8,15 +8,94 @@ pub struct AllowanceDataKey {
     pub spender: Identifier,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct NonceKey {
+    pub id: Identifier,
+    pub lane: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct CheckpointKey {
+    pub id: Identifier,
+    pub index: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct FreezeKey {
+    pub id: Identifier,
+    pub index: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub votes: BigInt,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FeeConfig {
+    pub fee_bps: u32,
+    pub min_fee: BigInt,
+    pub max_fee: BigInt,
+    pub collector: Identifier,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Accrual {
+    pub index: BigInt,
+    pub updated: u64,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Allowance(AllowanceDataKey),
     Balance(Identifier),
     Nonce(Identifier),
+    LaneNonce(NonceKey),
     State(Identifier),
+    Delegate(Identifier),
+    Checkpoint(CheckpointKey),
+    CkptCount(Identifier),
+    FeeExempt(Identifier),
+    AccrualIdx(Identifier),
+    Holder(u32),
+    HolderIdx(Identifier),
+    Spenders(Identifier),
+    FreezeRec(FreezeKey),
+    FreezeCnt(Identifier),
+    Freeze(Identifier),
+    AccountMax(Identifier),
+    MaxExempt(Identifier),
+    Limit(Identifier),
+    Outflows(Identifier),
     Admin,
     Decimals,
     Name,
     Symbol,
+    FeeConfig,
+    Index,
+    Oracle,
+    Rate,
+    Accrual,
+    Supply,
+    Underlying,
+    IconUri,
+    HomeDomain,
+    Desc,
+    IssuerUrl,
+    Version,
+    Holders,
+    Compliance,
+    MaxBalance,
+    Timelock,
+    Queue,
+    QueueNext,
+    MintWindow,
 }
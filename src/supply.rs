@@ -0,0 +1,21 @@
+use crate::storage_types::DataKey;
+use soroban_sdk::{BigInt, Env};
+
+pub fn read_supply(e: &Env) -> BigInt {
+    let key = DataKey::Supply;
+    if let Some(supply) = e.contract_data().get(key) {
+        supply.unwrap()
+    } else {
+        BigInt::zero(e)
+    }
+}
+
+pub fn increase_supply(e: &Env, shares: BigInt) {
+    let key = DataKey::Supply;
+    e.contract_data().set(key, read_supply(e) + shares);
+}
+
+pub fn decrease_supply(e: &Env, shares: BigInt) {
+    let key = DataKey::Supply;
+    e.contract_data().set(key, read_supply(e) - shares);
+}
@@ -1,10 +1,20 @@
 #![cfg(feature = "testutils")]
 
+use crate::allowance::Approval;
+use crate::amount::{format_amount, parse_amount};
 use crate::contract::TokenClient;
+use crate::fee::XferQuote;
+use crate::freeze::{FreezeMode, FreezeRecord};
+use crate::metadata::{MetaField, TokenMetadata};
+use crate::timelock::{Action, QueuedAction, Timelock};
+use crate::velocity::VelocityLimit;
 use ed25519_dalek::Keypair;
-use soroban_auth::{Ed25519Signature, Identifier, Signature, SignaturePayload, SignaturePayloadV0};
+use soroban_auth::{
+    AccountSignatures, Ed25519Signature, Identifier, Signature, SignaturePayload,
+    SignaturePayloadV0,
+};
 use soroban_sdk::testutils::ed25519::Sign;
-use soroban_sdk::{symbol, BigInt, Bytes, BytesN, Env, IntoVal};
+use soroban_sdk::{symbol, AccountId, BigInt, Bytes, BytesN, Env, IntoVal, RawVal, Symbol, Vec};
 
 pub fn register_test_contract(e: &Env, contract_id: &[u8; 32]) {
     let contract_id = BytesN::from_array(e, contract_id);
@@ -15,9 +25,150 @@ pub fn to_ed25519(e: &Env, kp: &Keypair) -> Identifier {
     Identifier::Ed25519(kp.public.to_bytes().into_val(e))
 }
 
+/// Builds the payload that authorizes a call to `function` on the token
+/// contract `contract_id`. Arguments are added in the order the contract
+/// checks them: the signer's identifier, the nonce, `valid_until`, then the
+/// function's own arguments.
+pub struct PayloadBuilder {
+    env: Env,
+    contract_id: BytesN<32>,
+    function: Symbol,
+    args: Vec<RawVal>,
+}
+
+impl PayloadBuilder {
+    pub fn new(env: &Env, contract_id: &BytesN<32>, function: Symbol) -> Self {
+        Self {
+            env: env.clone(),
+            contract_id: contract_id.clone(),
+            function,
+            args: Vec::new(env),
+        }
+    }
+
+    pub fn arg(mut self, arg: impl IntoVal<Env, RawVal>) -> Self {
+        self.args.push_back(arg.into_val(&self.env));
+        self
+    }
+
+    pub fn args(mut self, args: Vec<RawVal>) -> Self {
+        self.args.append(&args);
+        self
+    }
+
+    pub fn build(self) -> SignaturePayload {
+        SignaturePayload::V0(SignaturePayloadV0 {
+            function: self.function,
+            contract: self.contract_id,
+            network: self.env.ledger().network_passphrase(),
+            args: self.args,
+        })
+    }
+}
+
+/// Something that can authorize token operations: an ed25519 key, a Stellar
+/// account and its signers, or the invoker of the contract.
+pub trait TestSigner {
+    fn identifier(&self, env: &Env) -> Identifier;
+
+    fn sign_payload(&self, env: &Env, payload: SignaturePayload) -> Signature;
+
+    /// Whether the signer's operations consume a nonce. Invoker authorization
+    /// doesn't, and must always be given a zero nonce.
+    fn uses_nonce(&self) -> bool {
+        true
+    }
+
+    /// Called right before a call the signer authorized is made. Invoker
+    /// authorization makes the signer's account the source account here, so
+    /// signing alone never changes who the invoker is.
+    fn prepare_call(&self, _env: &Env) {}
+}
+
+impl TestSigner for Keypair {
+    fn identifier(&self, env: &Env) -> Identifier {
+        to_ed25519(env, self)
+    }
+
+    fn sign_payload(&self, env: &Env, payload: SignaturePayload) -> Signature {
+        Signature::Ed25519(Ed25519Signature {
+            public_key: self.public.to_bytes().into_val(env),
+            signature: self.sign(payload).unwrap().into_val(env),
+        })
+    }
+}
+
+/// A Stellar account that signs with some of its signers. The signers must
+/// meet the account's medium threshold.
+pub struct AccountSigner {
+    pub account_id: AccountId,
+    pub signers: std::vec::Vec<Keypair>,
+}
+
+impl TestSigner for AccountSigner {
+    fn identifier(&self, _env: &Env) -> Identifier {
+        Identifier::Account(self.account_id.clone())
+    }
+
+    fn sign_payload(&self, env: &Env, payload: SignaturePayload) -> Signature {
+        // Signatures must be ordered by public key.
+        let mut signers: std::vec::Vec<&Keypair> = self.signers.iter().collect();
+        signers.sort_by_key(|kp| kp.public.to_bytes());
+
+        let mut signatures = Vec::new(env);
+        for kp in signers {
+            signatures.push_back(Ed25519Signature {
+                public_key: kp.public.to_bytes().into_val(env),
+                signature: kp.sign(payload.clone()).unwrap().into_val(env),
+            });
+        }
+        Signature::Account(AccountSignatures {
+            account_id: self.account_id.clone(),
+            signatures,
+        })
+    }
+}
+
+/// A Stellar account that authorizes by invoking the contract itself.
+pub struct InvokerSigner {
+    pub account_id: AccountId,
+}
+
+impl TestSigner for InvokerSigner {
+    fn identifier(&self, _env: &Env) -> Identifier {
+        Identifier::Account(self.account_id.clone())
+    }
+
+    fn sign_payload(&self, _env: &Env, _payload: SignaturePayload) -> Signature {
+        Signature::Invoker
+    }
+
+    fn prepare_call(&self, env: &Env) {
+        env.set_source_account(&self.account_id);
+    }
+
+    fn uses_nonce(&self) -> bool {
+        false
+    }
+}
+
 pub struct Token {
     env: Env,
     contract_id: BytesN<32>,
+    lane: u64,
+    valid_until: Option<u32>,
+}
+
+/// A signed `xfer_relay` call, built by the sender and handed to a relayer to
+/// submit.
+pub struct SignedXferRelay {
+    pub auth: Signature,
+    pub nonce: BigInt,
+    pub valid_until: Option<u32>,
+    pub to: Identifier,
+    pub amount: BigInt,
+    pub relayer: Identifier,
+    pub fee: BigInt,
 }
 
 impl Token {
@@ -25,6 +176,28 @@ impl Token {
         Self {
             env: env.clone(),
             contract_id: BytesN::from_array(env, contract_id),
+            lane: 0,
+            valid_until: None,
+        }
+    }
+
+    /// Returns a wrapper that signs with nonces from `lane`.
+    pub fn with_lane(&self, lane: u64) -> Self {
+        Self {
+            env: self.env.clone(),
+            contract_id: self.contract_id.clone(),
+            lane,
+            valid_until: self.valid_until,
+        }
+    }
+
+    /// Returns a wrapper whose signatures expire after ledger `valid_until`.
+    pub fn with_valid_until(&self, valid_until: Option<u32>) -> Self {
+        Self {
+            env: self.env.clone(),
+            contract_id: self.contract_id.clone(),
+            lane: self.lane,
+            valid_until,
         }
     }
 
@@ -35,173 +208,189 @@ impl Token {
             .initialize(&admin, &decimals, &name, &symbol);
     }
 
+    pub fn init_wrap(&self, admin: &Identifier, underlying: &[u8; 32], name: &str, symbol: &str) {
+        let underlying = BytesN::from_array(&self.env, underlying);
+        let name: Bytes = name.into_val(&self.env);
+        let symbol: Bytes = symbol.into_val(&self.env);
+        TokenClient::new(&self.env, &self.contract_id).init_wrap(
+            &admin,
+            &underlying,
+            &name,
+            &symbol,
+        );
+    }
+
     pub fn nonce(&self, id: &Identifier) -> BigInt {
-        TokenClient::new(&self.env, &self.contract_id).nonce(&id)
+        TokenClient::new(&self.env, &self.contract_id).nonce(&id, &Some(self.lane))
     }
 
-    pub fn allowance(&self, from: &Identifier, spender: &Identifier) -> BigInt {
-        TokenClient::new(&self.env, &self.contract_id).allowance(&from, &spender)
+    fn signer_nonce(&self, signer: &impl TestSigner) -> BigInt {
+        if signer.uses_nonce() {
+            self.nonce(&signer.identifier(&self.env))
+        } else {
+            BigInt::zero(&self.env)
+        }
     }
 
-    pub fn approve(&self, from: &Keypair, spender: &Identifier, amount: &BigInt) {
-        let from_id = to_ed25519(&self.env, from);
-        let nonce = self.nonce(&from_id);
+    /// Returns a client for a call authorized by `signer`. Calls made with a
+    /// signature from `sign` should go through it.
+    pub fn client_for(&self, signer: &impl TestSigner) -> TokenClient {
+        signer.prepare_call(&self.env);
+        TokenClient::new(&self.env, &self.contract_id)
+    }
 
-        let msg = SignaturePayload::V0(SignaturePayloadV0 {
-            function: symbol!("approve"),
-            contract: self.contract_id.clone(),
-            network: self.env.ledger().network_passphrase(),
-            args: (from_id, &nonce, spender, amount).into_val(&self.env),
-        });
+    /// Signs a call to `function` with `args`, the arguments that follow
+    /// `valid_until`. Returns the signature and the nonce it was signed
+    /// with, which can be used to call any signed entry point, including
+    /// ones this wrapper doesn't cover.
+    pub fn sign(
+        &self,
+        signer: &impl TestSigner,
+        function: Symbol,
+        args: Vec<RawVal>,
+    ) -> (Signature, BigInt) {
+        let nonce = self.signer_nonce(signer);
+        let payload = PayloadBuilder::new(&self.env, &self.contract_id, function)
+            .arg(signer.identifier(&self.env))
+            .arg(nonce.clone())
+            .arg(self.valid_until)
+            .args(args)
+            .build();
+        (signer.sign_payload(&self.env, payload), nonce)
+    }
 
-        let auth = Signature::Ed25519(Ed25519Signature {
-            public_key: from.public.to_bytes().into_val(&self.env),
-            signature: from.sign(msg).unwrap().into_val(&self.env),
-        });
-        TokenClient::new(&self.env, &self.contract_id).approve(&auth, &nonce, &spender, &amount)
+    pub fn allowance(&self, from: &Identifier, spender: &Identifier) -> BigInt {
+        TokenClient::new(&self.env, &self.contract_id).allowance(&from, &spender)
+    }
+
+    pub fn approve(&self, from: &impl TestSigner, spender: &Identifier, amount: &BigInt) {
+        let (auth, nonce) = self.sign(
+            from,
+            symbol!("approve"),
+            (spender, amount).into_val(&self.env),
+        );
+        self.client_for(from)
+            .approve(&auth, &nonce, &self.valid_until, &spender, &amount)
     }
 
     pub fn balance(&self, id: &Identifier) -> BigInt {
         TokenClient::new(&self.env, &self.contract_id).balance(&id)
     }
 
-    pub fn is_frozen(&self, id: &Identifier) -> bool {
+    pub fn is_frozen(&self, id: &Identifier) -> FreezeMode {
         TokenClient::new(&self.env, &self.contract_id).is_frozen(&id)
     }
 
-    pub fn xfer(&self, from: &Keypair, to: &Identifier, amount: &BigInt) {
-        let from_id = to_ed25519(&self.env, from);
-        let nonce = self.nonce(&from_id);
-
-        let msg = SignaturePayload::V0(SignaturePayloadV0 {
-            function: symbol!("xfer"),
-            contract: self.contract_id.clone(),
-            network: self.env.ledger().network_passphrase(),
-            args: (from_id, &nonce, to, amount).into_val(&self.env),
-        });
+    pub fn xfer(&self, from: &impl TestSigner, to: &Identifier, amount: &BigInt) {
+        let (auth, nonce) = self.sign(from, symbol!("xfer"), (to, amount).into_val(&self.env));
 
-        let auth = Signature::Ed25519(Ed25519Signature {
-            public_key: BytesN::from_array(&self.env, &from.public.to_bytes()),
-            signature: from.sign(msg).unwrap().into_val(&self.env),
-        });
-
-        TokenClient::new(&self.env, &self.contract_id).xfer(&auth, &nonce, &to, &amount)
+        self.client_for(from)
+            .xfer(&auth, &nonce, &self.valid_until, &to, &amount)
     }
 
     pub fn xfer_from(
         &self,
-        spender: &Keypair,
+        spender: &impl TestSigner,
         from: &Identifier,
         to: &Identifier,
         amount: &BigInt,
     ) {
-        let spender_id = to_ed25519(&self.env, spender);
-        let nonce = self.nonce(&spender_id);
+        let (auth, nonce) = self.sign(
+            spender,
+            symbol!("xfer_from"),
+            (from, to, amount).into_val(&self.env),
+        );
+
+        self.client_for(spender)
+            .xfer_from(&auth, &nonce, &self.valid_until, &from, &to, &amount)
+    }
 
-        let msg = SignaturePayload::V0(SignaturePayloadV0 {
-            function: symbol!("xfer_from"),
-            contract: self.contract_id.clone(),
-            network: self.env.ledger().network_passphrase(),
-            args: (spender_id, &nonce, from, to, amount).into_val(&self.env),
-        });
+    pub fn burn(&self, admin: &impl TestSigner, from: &Identifier, amount: &BigInt) {
+        let (auth, nonce) = self.sign(admin, symbol!("burn"), (from, amount).into_val(&self.env));
+        self.client_for(admin)
+            .burn(&auth, &nonce, &self.valid_until, &from, &amount)
+    }
 
-        let auth = Signature::Ed25519(Ed25519Signature {
-            public_key: spender.public.to_bytes().into_val(&self.env),
-            signature: spender.sign(msg).unwrap().into_val(&self.env),
-        });
+    pub fn freeze(&self, admin: &impl TestSigner, id: &Identifier) {
+        self.freeze_with(admin, id, FreezeMode::Full, 0, None)
+    }
 
-        TokenClient::new(&self.env, &self.contract_id).xfer_from(&auth, &nonce, &from, &to, &amount)
+    pub fn freeze_with(
+        &self,
+        admin: &impl TestSigner,
+        id: &Identifier,
+        mode: FreezeMode,
+        reason: u32,
+        reference: Option<&str>,
+    ) {
+        let reference: Option<Bytes> = reference.map(|r| r.into_val(&self.env));
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("freeze"),
+            (id, mode, reason, &reference).into_val(&self.env),
+        );
+        self.client_for(admin).freeze(
+            &auth,
+            &nonce,
+            &self.valid_until,
+            &id,
+            &mode,
+            &reason,
+            &reference,
+        )
     }
 
-    pub fn burn(&self, admin: &Keypair, from: &Identifier, amount: &BigInt) {
-        let admin_id = to_ed25519(&self.env, admin);
-        let nonce = self.nonce(&admin_id);
+    pub fn mint(&self, admin: &impl TestSigner, to: &Identifier, amount: &BigInt) {
+        let (auth, nonce) = self.sign(admin, symbol!("mint"), (to, amount).into_val(&self.env));
+        self.client_for(admin)
+            .mint(&auth, &nonce, &self.valid_until, &to, &amount)
+    }
 
-        let msg = SignaturePayload::V0(SignaturePayloadV0 {
-            function: symbol!("burn"),
-            contract: self.contract_id.clone(),
-            network: self.env.ledger().network_passphrase(),
-            args: (admin_id, &nonce, from, amount).into_val(&self.env),
-        });
-        let auth = Signature::Ed25519(Ed25519Signature {
-            public_key: admin.public.to_bytes().into_val(&self.env),
-            signature: admin.sign(msg).unwrap().into_val(&self.env),
-        });
-        TokenClient::new(&self.env, &self.contract_id).burn(&auth, &nonce, &from, &amount)
-    }
-
-    pub fn freeze(&self, admin: &Keypair, id: &Identifier) {
-        let admin_id = to_ed25519(&self.env, admin);
-        let nonce = self.nonce(&admin_id);
-
-        let msg = SignaturePayload::V0(SignaturePayloadV0 {
-            function: symbol!("freeze"),
-            contract: self.contract_id.clone(),
-            network: self.env.ledger().network_passphrase(),
-            args: (admin_id, &nonce, id).into_val(&self.env),
-        });
-        let auth = Signature::Ed25519(Ed25519Signature {
-            public_key: admin.public.to_bytes().into_val(&self.env),
-            signature: admin.sign(msg).unwrap().into_val(&self.env),
-        });
-        TokenClient::new(&self.env, &self.contract_id).freeze(&auth, &nonce, &id)
-    }
-
-    pub fn mint(&self, admin: &Keypair, to: &Identifier, amount: &BigInt) {
-        let admin_id = to_ed25519(&self.env, admin);
-        let nonce = self.nonce(&admin_id);
-
-        let msg = SignaturePayload::V0(SignaturePayloadV0 {
-            function: symbol!("mint"),
-            contract: self.contract_id.clone(),
-            network: self.env.ledger().network_passphrase(),
-            args: (admin_id, &nonce, to, amount).into_val(&self.env),
-        });
-        let auth = Signature::Ed25519(Ed25519Signature {
-            public_key: admin.public.to_bytes().into_val(&self.env),
-            signature: admin.sign(msg).unwrap().into_val(&self.env),
-        });
-        TokenClient::new(&self.env, &self.contract_id).mint(&auth, &nonce, &to, &amount)
-    }
-
-    pub fn set_admin(&self, admin: &Keypair, new_admin: &Identifier) {
-        let admin_id = to_ed25519(&self.env, admin);
-        let nonce = self.nonce(&admin_id);
-
-        let msg = SignaturePayload::V0(SignaturePayloadV0 {
-            function: symbol!("set_admin"),
-            contract: self.contract_id.clone(),
-            network: self.env.ledger().network_passphrase(),
-            args: (admin_id, &nonce, new_admin).into_val(&self.env),
-        });
-        let auth = Signature::Ed25519(Ed25519Signature {
-            public_key: admin.public.to_bytes().into_val(&self.env),
-            signature: admin.sign(msg).unwrap().into_val(&self.env),
-        });
-        TokenClient::new(&self.env, &self.contract_id).set_admin(&auth, &nonce, &new_admin)
-    }
-
-    pub fn unfreeze(&self, admin: &Keypair, id: &Identifier) {
-        let admin_id = to_ed25519(&self.env, admin);
-        let nonce = self.nonce(&admin_id);
-
-        let msg = SignaturePayload::V0(SignaturePayloadV0 {
-            function: symbol!("unfreeze"),
-            contract: self.contract_id.clone(),
-            network: self.env.ledger().network_passphrase(),
-            args: (admin_id, &nonce, id).into_val(&self.env),
-        });
-        let auth = Signature::Ed25519(Ed25519Signature {
-            public_key: admin.public.to_bytes().into_val(&self.env),
-            signature: admin.sign(msg).unwrap().into_val(&self.env),
-        });
-        TokenClient::new(&self.env, &self.contract_id).unfreeze(&auth, &nonce, &id)
+    pub fn set_admin(&self, admin: &impl TestSigner, new_admin: &Identifier) {
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_admin"),
+            (new_admin,).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .set_admin(&auth, &nonce, &self.valid_until, &new_admin)
+    }
+
+    pub fn unfreeze(&self, admin: &impl TestSigner, id: &Identifier) {
+        self.unfreeze_with(admin, id, 0, None)
+    }
+
+    pub fn unfreeze_with(
+        &self,
+        admin: &impl TestSigner,
+        id: &Identifier,
+        reason: u32,
+        reference: Option<&str>,
+    ) {
+        let reference: Option<Bytes> = reference.map(|r| r.into_val(&self.env));
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("unfreeze"),
+            (id, reason, &reference).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .unfreeze(&auth, &nonce, &self.valid_until, &id, &reason, &reference)
     }
 
     pub fn decimals(&self) -> u32 {
         TokenClient::new(&self.env, &self.contract_id).decimals()
     }
 
+    /// Parses a decimal string such as "1.5" into raw units at the token's
+    /// decimals.
+    pub fn amount(&self, amount: &str) -> BigInt {
+        parse_amount(&self.env, amount, self.decimals()).unwrap()
+    }
+
+    pub fn format_amount(&self, amount: &BigInt) -> std::string::String {
+        format_amount(&self.env, amount, self.decimals())
+    }
+
     pub fn name(&self) -> Bytes {
         TokenClient::new(&self.env, &self.contract_id).name()
     }
@@ -209,4 +398,314 @@ impl Token {
     pub fn symbol(&self) -> Bytes {
         TokenClient::new(&self.env, &self.contract_id).symbol()
     }
+
+    pub fn delegate(&self, from: &impl TestSigner, delegatee: &Identifier) {
+        let (auth, nonce) = self.sign(from, symbol!("delegate"), (delegatee,).into_val(&self.env));
+        self.client_for(from)
+            .delegate(&auth, &nonce, &self.valid_until, &delegatee)
+    }
+
+    pub fn votes(&self, id: &Identifier) -> BigInt {
+        TokenClient::new(&self.env, &self.contract_id).votes(&id)
+    }
+
+    pub fn votes_at(&self, id: &Identifier, ledger: u32) -> BigInt {
+        TokenClient::new(&self.env, &self.contract_id).votes_at(&id, &ledger)
+    }
+
+    pub fn sign_xfer_relay(
+        &self,
+        from: &impl TestSigner,
+        to: &Identifier,
+        amount: &BigInt,
+        relayer: &Identifier,
+        fee: &BigInt,
+    ) -> SignedXferRelay {
+        let (auth, nonce) = self.sign(
+            from,
+            symbol!("xfer_relay"),
+            (to, amount, relayer, fee).into_val(&self.env),
+        );
+        SignedXferRelay {
+            auth,
+            nonce,
+            valid_until: self.valid_until,
+            to: to.clone(),
+            amount: amount.clone(),
+            relayer: relayer.clone(),
+            fee: fee.clone(),
+        }
+    }
+
+    pub fn submit_xfer_relay(&self, signed: &SignedXferRelay) {
+        TokenClient::new(&self.env, &self.contract_id).xfer_relay(
+            &signed.auth,
+            &signed.nonce,
+            &signed.valid_until,
+            &signed.to,
+            &signed.amount,
+            &signed.relayer,
+            &signed.fee,
+        )
+    }
+
+    pub fn xfer_relay(
+        &self,
+        from: &impl TestSigner,
+        to: &Identifier,
+        amount: &BigInt,
+        relayer: &Identifier,
+        fee: &BigInt,
+    ) {
+        let signed = self.sign_xfer_relay(from, to, amount, relayer, fee);
+        from.prepare_call(&self.env);
+        self.submit_xfer_relay(&signed)
+    }
+
+    pub fn set_fee(
+        &self,
+        admin: &impl TestSigner,
+        fee_bps: u32,
+        min_fee: &BigInt,
+        max_fee: &BigInt,
+        collector: &Identifier,
+    ) {
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_fee"),
+            (fee_bps, min_fee, max_fee, collector).into_val(&self.env),
+        );
+        self.client_for(admin).set_fee(
+            &auth,
+            &nonce,
+            &self.valid_until,
+            &fee_bps,
+            &min_fee,
+            &max_fee,
+            &collector,
+        )
+    }
+
+    pub fn set_exempt(&self, admin: &impl TestSigner, id: &Identifier, exempt: bool) {
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_exempt"),
+            (id, exempt).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .set_exempt(&auth, &nonce, &self.valid_until, &id, &exempt)
+    }
+
+    pub fn quote_xfer(&self, from: &Identifier, to: &Identifier, amount: &BigInt) -> XferQuote {
+        TokenClient::new(&self.env, &self.contract_id).quote_xfer(&from, &to, &amount)
+    }
+
+    pub fn shares_of(&self, id: &Identifier) -> BigInt {
+        TokenClient::new(&self.env, &self.contract_id).shares_of(&id)
+    }
+
+    pub fn index(&self) -> BigInt {
+        TokenClient::new(&self.env, &self.contract_id).index()
+    }
+
+    pub fn set_oracle(&self, admin: &impl TestSigner, oracle: &Identifier) {
+        let (auth, nonce) = self.sign(admin, symbol!("set_oracle"), (oracle,).into_val(&self.env));
+        self.client_for(admin)
+            .set_oracle(&auth, &nonce, &self.valid_until, &oracle)
+    }
+
+    pub fn rebase(&self, auth: &impl TestSigner, index: &BigInt) {
+        let (signature, nonce) = self.sign(auth, symbol!("rebase"), (index,).into_val(&self.env));
+        self.client_for(auth)
+            .rebase(&signature, &nonce, &self.valid_until, &index)
+    }
+
+    pub fn set_rate(&self, admin: &impl TestSigner, rate: &BigInt) {
+        let (auth, nonce) = self.sign(admin, symbol!("set_rate"), (rate,).into_val(&self.env));
+        self.client_for(admin)
+            .set_rate(&auth, &nonce, &self.valid_until, &rate)
+    }
+
+    pub fn accrued(&self, id: &Identifier) -> BigInt {
+        TokenClient::new(&self.env, &self.contract_id).accrued(&id)
+    }
+
+    pub fn supply(&self) -> BigInt {
+        TokenClient::new(&self.env, &self.contract_id).supply()
+    }
+
+    pub fn deposit(&self, from: &impl TestSigner, amount: &BigInt) {
+        let (auth, nonce) = self.sign(from, symbol!("deposit"), (amount,).into_val(&self.env));
+        self.client_for(from)
+            .deposit(&auth, &nonce, &self.valid_until, &amount)
+    }
+
+    pub fn withdraw(&self, from: &impl TestSigner, amount: &BigInt) {
+        let (auth, nonce) = self.sign(from, symbol!("withdraw"), (amount,).into_val(&self.env));
+        self.client_for(from)
+            .withdraw(&auth, &nonce, &self.valid_until, &amount)
+    }
+
+    pub fn set_meta(&self, admin: &impl TestSigner, name: &str, symbol: &str) {
+        let name: Bytes = name.into_val(&self.env);
+        let symbol: Bytes = symbol.into_val(&self.env);
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_meta"),
+            (&name, &symbol).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .set_meta(&auth, &nonce, &self.valid_until, &name, &symbol)
+    }
+
+    pub fn set_field(&self, admin: &impl TestSigner, field: MetaField, value: &str) {
+        let value: Bytes = value.into_val(&self.env);
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_field"),
+            (field, &value).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .set_field(&auth, &nonce, &self.valid_until, &field, &value)
+    }
+
+    pub fn metadata(&self) -> TokenMetadata {
+        TokenClient::new(&self.env, &self.contract_id).metadata()
+    }
+
+    pub fn version(&self) -> u32 {
+        TokenClient::new(&self.env, &self.contract_id).version()
+    }
+
+    pub fn migrate(&self, admin: &impl TestSigner) {
+        let (auth, nonce) = self.sign(admin, symbol!("migrate"), Vec::new(&self.env));
+        self.client_for(admin)
+            .migrate(&auth, &nonce, &self.valid_until)
+    }
+
+    pub fn invalidate(&self, from: &impl TestSigner, up_to: &BigInt) {
+        let (auth, nonce) = self.sign(from, symbol!("invalidate"), (up_to,).into_val(&self.env));
+        self.client_for(from)
+            .invalidate(&auth, &nonce, &self.valid_until, &up_to)
+    }
+
+    pub fn holder_cnt(&self) -> u32 {
+        TokenClient::new(&self.env, &self.contract_id).holder_cnt()
+    }
+
+    pub fn holders(&self, start: u32, limit: u32) -> Vec<Identifier> {
+        TokenClient::new(&self.env, &self.contract_id).holders(&start, &limit)
+    }
+
+    pub fn allowances(&self, from: &Identifier, start: u32, limit: u32) -> Vec<Approval> {
+        TokenClient::new(&self.env, &self.contract_id).allowances(&from, &start, &limit)
+    }
+
+    pub fn revoke_all(&self, from: &impl TestSigner) {
+        let (auth, nonce) = self.sign(from, symbol!("revoke_all"), Vec::new(&self.env));
+        self.client_for(from)
+            .revoke_all(&auth, &nonce, &self.valid_until)
+    }
+
+    pub fn freeze_rec(&self, id: &Identifier) -> Option<FreezeRecord> {
+        TokenClient::new(&self.env, &self.contract_id).freeze_rec(&id)
+    }
+
+    pub fn freeze_log(&self, id: &Identifier) -> Vec<FreezeRecord> {
+        TokenClient::new(&self.env, &self.contract_id).freeze_log(&id)
+    }
+
+    pub fn set_comply(&self, admin: &impl TestSigner, compliance: Option<&[u8; 32]>) {
+        let compliance = compliance.map(|id| BytesN::from_array(&self.env, id));
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_comply"),
+            (&compliance,).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .set_comply(&auth, &nonce, &self.valid_until, &compliance)
+    }
+
+    pub fn check_xfer(&self, from: &Identifier, to: &Identifier, amount: &BigInt) -> u32 {
+        TokenClient::new(&self.env, &self.contract_id).check_xfer(&from, &to, &amount)
+    }
+
+    pub fn set_max(&self, admin: &impl TestSigner, max: Option<&BigInt>) {
+        let max = max.cloned();
+        let (auth, nonce) = self.sign(admin, symbol!("set_max"), (&max,).into_val(&self.env));
+        self.client_for(admin)
+            .set_max(&auth, &nonce, &self.valid_until, &max)
+    }
+
+    pub fn set_max_id(&self, admin: &impl TestSigner, id: &Identifier, max: Option<&BigInt>) {
+        let max = max.cloned();
+        let (auth, nonce) = self.sign(admin, symbol!("set_max_id"), (id, &max).into_val(&self.env));
+        self.client_for(admin)
+            .set_max_id(&auth, &nonce, &self.valid_until, &id, &max)
+    }
+
+    pub fn set_max_ex(&self, admin: &impl TestSigner, id: &Identifier, exempt: bool) {
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_max_ex"),
+            (id, exempt).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .set_max_ex(&auth, &nonce, &self.valid_until, &id, &exempt)
+    }
+
+    pub fn max_bal(&self, id: &Identifier) -> Option<BigInt> {
+        TokenClient::new(&self.env, &self.contract_id).max_bal(&id)
+    }
+
+    pub fn set_limit(
+        &self,
+        admin: &impl TestSigner,
+        id: &Identifier,
+        limit: Option<VelocityLimit>,
+    ) {
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_limit"),
+            (id, &limit).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .set_limit(&auth, &nonce, &self.valid_until, &id, &limit)
+    }
+
+    pub fn remaining(&self, id: &Identifier) -> Option<BigInt> {
+        TokenClient::new(&self.env, &self.contract_id).remaining(&id)
+    }
+
+    pub fn set_tlock(&self, admin: &impl TestSigner, timelock: Option<Timelock>) {
+        let (auth, nonce) = self.sign(
+            admin,
+            symbol!("set_tlock"),
+            (&timelock,).into_val(&self.env),
+        );
+        self.client_for(admin)
+            .set_tlock(&auth, &nonce, &self.valid_until, &timelock)
+    }
+
+    pub fn queue(&self, admin: &impl TestSigner, action: Action) -> u32 {
+        let (auth, nonce) = self.sign(admin, symbol!("queue"), (&action,).into_val(&self.env));
+        self.client_for(admin)
+            .queue(&auth, &nonce, &self.valid_until, &action)
+    }
+
+    pub fn execute(&self, admin: &impl TestSigner, id: u32) {
+        let (auth, nonce) = self.sign(admin, symbol!("execute"), (id,).into_val(&self.env));
+        self.client_for(admin)
+            .execute(&auth, &nonce, &self.valid_until, &id)
+    }
+
+    pub fn cancel(&self, admin: &impl TestSigner, id: u32) {
+        let (auth, nonce) = self.sign(admin, symbol!("cancel"), (id,).into_val(&self.env));
+        self.client_for(admin)
+            .cancel(&auth, &nonce, &self.valid_until, &id)
+    }
+
+    pub fn queued(&self) -> Vec<QueuedAction> {
+        TokenClient::new(&self.env, &self.contract_id).queued()
+    }
 }
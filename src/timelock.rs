@@ -0,0 +1,226 @@
+use crate::metadata::MetaField;
+use crate::storage_types::{DataKey, FeeConfig};
+use crate::velocity::VelocityLimit;
+use soroban_auth::Identifier;
+use soroban_sdk::{contracttype, BigInt, Bytes, BytesN, Env, Vec};
+
+/// While a timelock is set, admin actions must be queued and can only be
+/// executed `delay` seconds later. Mints totalling up to `mint_max` within
+/// each `delay` seconds are exempt, as are `freeze` and `unfreeze`: freezing
+/// is the response to a compromised account and can't wait out the delay, and
+/// it moves no funds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Timelock {
+    pub delay: u64,
+    pub mint_max: BigInt,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MintAction {
+    pub to: Identifier,
+    pub amount: BigInt,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MetaAction {
+    pub name: Bytes,
+    pub symbol: Bytes,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FieldAction {
+    pub field: MetaField,
+    pub value: Bytes,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ExemptAction {
+    pub id: Identifier,
+    pub exempt: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BurnAction {
+    pub from: Identifier,
+    pub amount: BigInt,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MaxAction {
+    pub id: Identifier,
+    pub max: Option<BigInt>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct LimitAction {
+    pub id: Identifier,
+    pub limit: Option<VelocityLimit>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Action {
+    Mint(MintAction),
+    SetAdmin(Identifier),
+    SetMeta(MetaAction),
+    SetField(FieldAction),
+    SetFee(FeeConfig),
+    SetExempt(ExemptAction),
+    SetOracle(Identifier),
+    Rebase(BigInt),
+    SetRate(BigInt),
+    SetTlock(Timelock),
+    Unlock,
+    Burn(BurnAction),
+    SetComply(Option<BytesN<32>>),
+    SetMax(Option<BigInt>),
+    SetMaxId(MaxAction),
+    SetMaxEx(ExemptAction),
+    SetLimit(LimitAction),
+}
+
+// Mints made directly since `start`, counted against `mint_max`.
+#[derive(Clone)]
+#[contracttype]
+pub struct MintWindow {
+    pub start: u64,
+    pub minted: BigInt,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct QueuedAction {
+    pub id: u32,
+    pub action: Action,
+    pub eta: u64,
+}
+
+pub fn read_timelock(e: &Env) -> Option<Timelock> {
+    let key = DataKey::Timelock;
+    if let Some(timelock) = e.contract_data().get(key) {
+        Some(timelock.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn write_timelock(e: &Env, timelock: Option<Timelock>) {
+    let key = DataKey::Timelock;
+    if let Some(timelock) = timelock {
+        if timelock.mint_max < BigInt::zero(e) {
+            panic!("mint_max must not be negative");
+        }
+        e.contract_data().set(key, timelock);
+    } else {
+        e.contract_data().remove(key);
+    }
+}
+
+/// Panics if a timelock is set, since the action must then be queued.
+pub fn check_unlocked(e: &Env) {
+    if read_timelock(e).is_some() {
+        panic!("action must be queued");
+    }
+}
+
+/// Counts a direct mint of `amount` against the timelock's exemption, and
+/// panics if it would take the mints in the current window above
+/// `mint_max`.
+pub fn use_mint_allowance(e: &Env, amount: &BigInt) {
+    let timelock = if let Some(timelock) = read_timelock(e) {
+        timelock
+    } else {
+        return;
+    };
+    let key = DataKey::MintWindow;
+    let now = e.ledger().timestamp();
+    let mut window = if let Some(window) = e.contract_data().get(key.clone()) {
+        window.unwrap()
+    } else {
+        MintWindow {
+            start: now,
+            minted: BigInt::zero(e),
+        }
+    };
+    if now >= window.start.saturating_add(timelock.delay) {
+        window = MintWindow {
+            start: now,
+            minted: BigInt::zero(e),
+        };
+    }
+    window.minted = window.minted + amount.clone();
+    if window.minted > timelock.mint_max {
+        panic!("action must be queued");
+    }
+    e.contract_data().set(key, window);
+}
+
+pub fn read_queue(e: &Env) -> Vec<QueuedAction> {
+    let key = DataKey::Queue;
+    if let Some(queue) = e.contract_data().get(key) {
+        queue.unwrap()
+    } else {
+        Vec::new(e)
+    }
+}
+
+fn write_queue(e: &Env, queue: Vec<QueuedAction>) {
+    let key = DataKey::Queue;
+    e.contract_data().set(key, queue);
+}
+
+fn next_queue_id(e: &Env) -> u32 {
+    let key = DataKey::QueueNext;
+    let id = if let Some(id) = e.contract_data().get(key.clone()) {
+        id.unwrap()
+    } else {
+        0
+    };
+    e.contract_data().set(key, id + 1);
+    id
+}
+
+pub fn enqueue(e: &Env, action: Action) -> QueuedAction {
+    let timelock = if let Some(timelock) = read_timelock(e) {
+        timelock
+    } else {
+        panic!("timelock not enabled")
+    };
+    let queued = QueuedAction {
+        id: next_queue_id(e),
+        action,
+        eta: e.ledger().timestamp().saturating_add(timelock.delay),
+    };
+    let mut queue = read_queue(e);
+    queue.push_back(queued.clone());
+    write_queue(e, queue);
+    queued
+}
+
+/// Removes the queued action `id` and returns it.
+pub fn dequeue(e: &Env, id: u32) -> QueuedAction {
+    let mut found = None;
+    let mut queue = Vec::new(e);
+    for queued in read_queue(e).iter() {
+        let queued = queued.unwrap();
+        if queued.id == id {
+            found = Some(queued);
+        } else {
+            queue.push_back(queued);
+        }
+    }
+    if let Some(queued) = found {
+        write_queue(e, queue);
+        queued
+    } else {
+        panic!("no queued action")
+    }
+}
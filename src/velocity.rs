@@ -0,0 +1,106 @@
+use crate::storage_types::DataKey;
+use soroban_auth::Identifier;
+use soroban_sdk::{contracttype, BigInt, Env, Vec};
+
+/// At most `amount` may leave an account in any `window` seconds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct VelocityLimit {
+    pub amount: BigInt,
+    pub window: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Outflow {
+    pub timestamp: u64,
+    pub amount: BigInt,
+}
+
+pub fn read_velocity_limit(e: &Env, id: Identifier) -> Option<VelocityLimit> {
+    let key = DataKey::Limit(id);
+    if let Some(limit) = e.contract_data().get(key) {
+        Some(limit.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn write_velocity_limit(e: &Env, id: Identifier, limit: Option<VelocityLimit>) {
+    let key = DataKey::Limit(id.clone());
+    if let Some(limit) = limit {
+        if limit.amount < BigInt::zero(e) {
+            panic!("limit must not be negative");
+        }
+        e.contract_data().set(key, limit);
+    } else {
+        e.contract_data().remove(key);
+        e.contract_data().remove(DataKey::Outflows(id));
+    }
+}
+
+// Returns the outflows still inside the window ending now.
+fn read_outflows(e: &Env, id: Identifier, window: u64) -> Vec<Outflow> {
+    let key = DataKey::Outflows(id);
+    let outflows: Vec<Outflow> = if let Some(outflows) = e.contract_data().get(key) {
+        outflows.unwrap()
+    } else {
+        Vec::new(e)
+    };
+    let now = e.ledger().timestamp();
+    let mut current = Vec::new(e);
+    for outflow in outflows.iter() {
+        let outflow = outflow.unwrap();
+        if outflow.timestamp.saturating_add(window) > now {
+            current.push_back(outflow);
+        }
+    }
+    current
+}
+
+fn sum(e: &Env, outflows: &Vec<Outflow>) -> BigInt {
+    let mut total = BigInt::zero(e);
+    for outflow in outflows.iter() {
+        total = total + outflow.unwrap().amount;
+    }
+    total
+}
+
+/// Returns how much more `id` may send in the current window, or `None` if it
+/// has no limit.
+pub fn read_remaining(e: &Env, id: Identifier) -> Option<BigInt> {
+    let limit = if let Some(limit) = read_velocity_limit(e, id.clone()) {
+        limit
+    } else {
+        return None;
+    };
+    let spent = sum(e, &read_outflows(e, id, limit.window));
+    if spent >= limit.amount {
+        Some(BigInt::zero(e))
+    } else {
+        Some(limit.amount - spent)
+    }
+}
+
+pub fn record_outflow(e: &Env, id: Identifier, amount: BigInt) {
+    // Only money leaving the account counts; a negative amount must never
+    // free up room in the window.
+    if amount <= BigInt::zero(e) {
+        return;
+    }
+    let limit = if let Some(limit) = read_velocity_limit(e, id.clone()) {
+        limit
+    } else {
+        return;
+    };
+    let mut outflows = read_outflows(e, id.clone(), limit.window);
+    if sum(e, &outflows) + amount.clone() > limit.amount {
+        panic!("velocity limit exceeded");
+    }
+    outflows.push_back(Outflow {
+        timestamp: e.ledger().timestamp(),
+        amount,
+    });
+    let key = DataKey::Outflows(id);
+    e.contract_data().set(key, outflows);
+}
@@ -0,0 +1,103 @@
+use crate::storage_types::{Checkpoint, CheckpointKey, DataKey};
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env};
+
+// Votes are counted in shares rather than token amounts, so that a rebase
+// changes every holder's balance without changing their relative weight.
+//
+// A delegatee's checkpoints are stored one per entry, under
+// `DataKey::Checkpoint` with indexes `0..count`, so that a long history
+// doesn't make each new checkpoint more expensive to write.
+
+pub fn read_delegate(e: &Env, id: Identifier) -> Option<Identifier> {
+    let key = DataKey::Delegate(id);
+    if let Some(delegatee) = e.contract_data().get(key) {
+        Some(delegatee.unwrap())
+    } else {
+        None
+    }
+}
+
+pub fn write_delegate(e: &Env, id: Identifier, delegatee: Identifier) {
+    let key = DataKey::Delegate(id);
+    e.contract_data().set(key, delegatee);
+}
+
+fn read_checkpoint_count(e: &Env, id: Identifier) -> u32 {
+    let key = DataKey::CkptCount(id);
+    if let Some(count) = e.contract_data().get(key) {
+        count.unwrap()
+    } else {
+        0
+    }
+}
+
+fn write_checkpoint_count(e: &Env, id: Identifier, count: u32) {
+    let key = DataKey::CkptCount(id);
+    e.contract_data().set(key, count);
+}
+
+fn read_checkpoint(e: &Env, id: Identifier, index: u32) -> Checkpoint {
+    let key = DataKey::Checkpoint(CheckpointKey { id, index });
+    e.contract_data().get_unchecked(key).unwrap()
+}
+
+fn write_checkpoint(e: &Env, id: Identifier, index: u32, checkpoint: Checkpoint) {
+    let key = DataKey::Checkpoint(CheckpointKey { id, index });
+    e.contract_data().set(key, checkpoint);
+}
+
+pub fn read_votes(e: &Env, id: Identifier) -> BigInt {
+    let count = read_checkpoint_count(e, id.clone());
+    if count == 0 {
+        return BigInt::zero(e);
+    }
+    read_checkpoint(e, id, count - 1).votes
+}
+
+pub fn read_votes_at(e: &Env, id: Identifier, ledger: u32) -> BigInt {
+    // Binary search for the number of checkpoints at or before `ledger`.
+    let mut low = 0;
+    let mut high = read_checkpoint_count(e, id.clone());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_checkpoint(e, id.clone(), mid).ledger <= ledger {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        return BigInt::zero(e);
+    }
+    read_checkpoint(e, id, low - 1).votes
+}
+
+fn write_votes(e: &Env, id: Identifier, votes: BigInt) {
+    let count = read_checkpoint_count(e, id.clone());
+    let checkpoint = Checkpoint {
+        ledger: e.ledger().sequence(),
+        votes,
+    };
+    // Only the last value written in a ledger is kept for that ledger.
+    if count > 0 && read_checkpoint(e, id.clone(), count - 1).ledger == checkpoint.ledger {
+        write_checkpoint(e, id, count - 1, checkpoint);
+    } else {
+        write_checkpoint(e, id.clone(), count, checkpoint);
+        write_checkpoint_count(e, id, count + 1);
+    }
+}
+
+pub fn add_delegated_votes(e: &Env, holder: Identifier, amount: BigInt) {
+    if let Some(delegatee) = read_delegate(e, holder) {
+        let votes = read_votes(e, delegatee.clone());
+        write_votes(e, delegatee, votes + amount);
+    }
+}
+
+pub fn sub_delegated_votes(e: &Env, holder: Identifier, amount: BigInt) {
+    if let Some(delegatee) = read_delegate(e, holder) {
+        let votes = read_votes(e, delegatee.clone());
+        write_votes(e, delegatee, votes - amount);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::storage_types::DataKey;
+use soroban_sdk::{BytesN, Env};
+
+pub fn read_underlying(e: &Env) -> BytesN<32> {
+    let key = DataKey::Underlying;
+    if let Some(underlying) = e.contract_data().get(key) {
+        underlying.unwrap()
+    } else {
+        panic!("not a wrapped token")
+    }
+}
+
+pub fn write_underlying(e: &Env, underlying: BytesN<32>) {
+    let key = DataKey::Underlying;
+    e.contract_data().set(key, underlying);
+}
+
+pub fn is_wrapped(e: &Env) -> bool {
+    let key = DataKey::Underlying;
+    e.contract_data().has(key)
+}
+
+/// Panics if the token is a wrapper, whose supply must stay backed one to one
+/// by the underlying tokens it holds.
+pub fn check_not_wrapped(e: &Env) {
+    if is_wrapped(e) {
+        panic!("not allowed on a wrapped token");
+    }
+}
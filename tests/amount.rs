@@ -0,0 +1,103 @@
+use ed25519_dalek::Keypair;
+use rand::{thread_rng, RngCore};
+use soroban_sdk::{BigInt, Env};
+use soroban_token_contract::amount::{format_amount, parse_amount, AmountError};
+use soroban_token_contract::testutils::{
+    register_test_contract as register_token, to_ed25519, Token,
+};
+
+fn generate_contract_id() -> [u8; 32] {
+    let mut id: [u8; 32] = Default::default();
+    thread_rng().fill_bytes(&mut id);
+    id
+}
+
+fn generate_keypair() -> Keypair {
+    Keypair::generate(&mut thread_rng())
+}
+
+#[test]
+fn parse() {
+    let e: Env = Default::default();
+    let parse = |s: &str| parse_amount(&e, s, 7);
+
+    assert_eq!(parse("12.3456789"), Ok(BigInt::from_u64(&e, 123456789)));
+    assert_eq!(parse("1"), Ok(BigInt::from_u64(&e, 10000000)));
+    assert_eq!(parse("1.5"), Ok(BigInt::from_u64(&e, 15000000)));
+    assert_eq!(parse(".5"), Ok(BigInt::from_u64(&e, 5000000)));
+    assert_eq!(parse("2."), Ok(BigInt::from_u64(&e, 20000000)));
+    assert_eq!(parse("0.0000001"), Ok(BigInt::from_u64(&e, 1)));
+    assert_eq!(parse("1.500000000"), Ok(BigInt::from_u64(&e, 15000000)));
+    assert_eq!(
+        parse("-1.5"),
+        Ok(BigInt::zero(&e) - BigInt::from_u64(&e, 15000000))
+    );
+    assert_eq!(parse_amount(&e, "42", 0), Ok(BigInt::from_u32(&e, 42)));
+}
+
+#[test]
+fn parse_rejects() {
+    let e: Env = Default::default();
+    let parse = |s: &str| parse_amount(&e, s, 7);
+
+    assert_eq!(parse(""), Err(AmountError::Empty));
+    assert_eq!(parse("."), Err(AmountError::Empty));
+    assert_eq!(parse("-"), Err(AmountError::Empty));
+    assert_eq!(parse("0.00000001"), Err(AmountError::TooPrecise));
+    assert_eq!(parse_amount(&e, "1.5", 0), Err(AmountError::TooPrecise));
+    assert_eq!(parse("1.2.3"), Err(AmountError::InvalidDigit));
+    assert_eq!(parse("1,5"), Err(AmountError::InvalidDigit));
+    assert_eq!(parse("+1"), Err(AmountError::InvalidDigit));
+    assert_eq!(parse(" 1"), Err(AmountError::InvalidDigit));
+}
+
+#[test]
+fn format() {
+    let e: Env = Default::default();
+    let format = |n: u64| format_amount(&e, &BigInt::from_u64(&e, n), 7);
+
+    assert_eq!(format(123456789), "12.3456789");
+    assert_eq!(format(10000000), "1");
+    assert_eq!(format(15000000), "1.5");
+    assert_eq!(format(1), "0.0000001");
+    assert_eq!(format(0), "0");
+    assert_eq!(
+        format_amount(&e, &(BigInt::zero(&e) - BigInt::from_u32(&e, 5)), 1),
+        "-0.5"
+    );
+    assert_eq!(format_amount(&e, &BigInt::from_u32(&e, 42), 0), "42");
+}
+
+#[test]
+fn round_trip() {
+    let e: Env = Default::default();
+    for s in [
+        "0",
+        "1",
+        "0.1",
+        "12.3456789",
+        "-7.25",
+        "18446744073709551616.5",
+    ] {
+        let amount = parse_amount(&e, s, 7).unwrap();
+        assert_eq!(format_amount(&e, &amount, 7), s);
+    }
+}
+
+#[test]
+fn token_amounts() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 2, "name", "symbol");
+    token.mint(&admin1, &user1_id, &token.amount("10.25"));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1025));
+    assert_eq!(token.format_amount(&token.balance(&user1_id)), "10.25");
+}
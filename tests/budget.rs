@@ -0,0 +1,476 @@
+//! Measures the host CPU instructions and memory each entry point consumes
+//! and compares them against `tests/budget.txt`. A cost more than
+//! `TOLERANCE_PCT` percent above its baseline fails the test, as does an
+//! entry point missing from the baseline.
+//!
+//! The measured table is written to `target/budget.txt`. Run with
+//! `BUDGET_RECORD=1 cargo test --test budget` to replace the baseline with it.
+
+use ed25519_dalek::Keypair;
+use rand::{thread_rng, RngCore};
+use soroban_auth::Identifier;
+use soroban_sdk::testutils::{Ledger, LedgerInfo};
+use soroban_sdk::{symbol, BigInt, Bytes, BytesN, Env, IntoVal};
+use soroban_token_contract::testutils::{
+    register_test_contract as register_token, to_ed25519, Token,
+};
+use soroban_token_contract::{Action, FreezeMode, MetaField, Timelock, TokenClient, VelocityLimit};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const TOLERANCE_PCT: u64 = 5;
+
+fn generate_contract_id() -> [u8; 32] {
+    let mut id: [u8; 32] = Default::default();
+    thread_rng().fill_bytes(&mut id);
+    id
+}
+
+fn generate_keypair() -> Keypair {
+    Keypair::generate(&mut thread_rng())
+}
+
+fn set_ledger(e: &Env, sequence_number: u32, timestamp: u64) {
+    e.ledger().set(LedgerInfo {
+        protocol_version: 1,
+        sequence_number,
+        timestamp,
+        network_passphrase: vec![0],
+        base_reserve: 10,
+    });
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Cost {
+    cpu: u64,
+    mem: u64,
+}
+
+// Only the contract call is measured; signing and reading nonces happen
+// before the budget is reset.
+fn measure(e: &Env, f: impl FnOnce()) -> Cost {
+    e.budget().reset();
+    f();
+    Cost {
+        cpu: e.budget().cpu_instruction_cost(),
+        mem: e.budget().memory_bytes_cost(),
+    }
+}
+
+fn parse_baseline(text: &str) -> BTreeMap<String, Cost> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let cost = Cost {
+                cpu: fields[1].parse().unwrap(),
+                mem: fields[2].parse().unwrap(),
+            };
+            (fields[0].to_string(), cost)
+        })
+        .collect()
+}
+
+fn format_table(costs: &[(&str, Cost)]) -> String {
+    let mut table = String::from("# entry point, cpu instructions, memory bytes\n");
+    for (name, cost) in costs {
+        table.push_str(&format!("{:<12} {:>12} {:>12}\n", name, cost.cpu, cost.mem));
+    }
+    table
+}
+
+fn exceeds(measured: u64, baseline: u64) -> bool {
+    measured * 100 > baseline * (100 + TOLERANCE_PCT)
+}
+
+#[test]
+fn budget() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let client = TokenClient::new(&e, &BytesN::from_array(&e, &contract_id));
+
+    let admin1 = generate_keypair();
+    let admin2 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let user3 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let admin2_id = to_ed25519(&e, &admin2);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let user3_id = to_ed25519(&e, &user3);
+    let amount = BigInt::from_u32(&e, 100);
+
+    let mut costs = Vec::new();
+    set_ledger(&e, 1, 1000);
+
+    let name: Bytes = "name".into_val(&e);
+    let symbol: Bytes = "symbol".into_val(&e);
+    costs.push((
+        "initialize",
+        measure(&e, || client.initialize(&admin1_id, &7, &name, &symbol)),
+    ));
+
+    let args = (&user1_id, &amount).into_val(&e);
+    let (auth, nonce) = token.sign(&admin1, symbol!("mint"), args);
+    costs.push((
+        "mint",
+        measure(&e, || client.mint(&auth, &nonce, &None, &user1_id, &amount)),
+    ));
+
+    let args = (&user3_id, &amount).into_val(&e);
+    let (auth, nonce) = token.sign(&user1, symbol!("approve"), args);
+    costs.push((
+        "approve",
+        measure(&e, || {
+            client.approve(&auth, &nonce, &None, &user3_id, &amount)
+        }),
+    ));
+
+    let half = BigInt::from_u32(&e, 50);
+    let args = (&user2_id, &half).into_val(&e);
+    let (auth, nonce) = token.sign(&user1, symbol!("xfer"), args);
+    costs.push((
+        "xfer",
+        measure(&e, || client.xfer(&auth, &nonce, &None, &user2_id, &half)),
+    ));
+
+    let args = (&user1_id, &user2_id, &half).into_val(&e);
+    let (auth, nonce) = token.sign(&user3, symbol!("xfer_from"), args);
+    costs.push((
+        "xfer_from",
+        measure(&e, || {
+            client.xfer_from(&auth, &nonce, &None, &user1_id, &user2_id, &half)
+        }),
+    ));
+
+    let args = (&user1_id,).into_val(&e);
+    let (auth, nonce) = token.sign(&user2, symbol!("delegate"), args);
+    costs.push((
+        "delegate",
+        measure(&e, || client.delegate(&auth, &nonce, &None, &user1_id)),
+    ));
+
+    let one = BigInt::from_u32(&e, 1);
+    let args = (&user1_id, &half, &user3_id, &one).into_val(&e);
+    let (auth, nonce) = token.sign(&user2, symbol!("xfer_relay"), args);
+    costs.push((
+        "xfer_relay",
+        measure(&e, || {
+            client.xfer_relay(&auth, &nonce, &None, &user1_id, &half, &user3_id, &one)
+        }),
+    ));
+
+    let args = (&user2_id, &one).into_val(&e);
+    let (auth, nonce) = token.sign(&admin1, symbol!("burn"), args);
+    costs.push((
+        "burn",
+        measure(&e, || client.burn(&auth, &nonce, &None, &user2_id, &one)),
+    ));
+
+    let reference: Option<Bytes> = None;
+    let args = (&user3_id, FreezeMode::Full, 0u32, &reference).into_val(&e);
+    let (auth, nonce) = token.sign(&admin1, symbol!("freeze"), args);
+    costs.push((
+        "freeze",
+        measure(&e, || {
+            client.freeze(
+                &auth,
+                &nonce,
+                &None,
+                &user3_id,
+                &FreezeMode::Full,
+                &0,
+                &reference,
+            )
+        }),
+    ));
+
+    let args = (&user3_id, 0u32, &reference).into_val(&e);
+    let (auth, nonce) = token.sign(&admin1, symbol!("unfreeze"), args);
+    costs.push((
+        "unfreeze",
+        measure(&e, || {
+            client.unfreeze(&auth, &nonce, &None, &user3_id, &0, &reference)
+        }),
+    ));
+
+    let args = (&admin2_id,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin1, symbol!("set_admin"), args);
+    costs.push((
+        "set_admin",
+        measure(&e, || client.set_admin(&auth, &nonce, &None, &admin2_id)),
+    ));
+
+    let min_fee = BigInt::from_u32(&e, 1);
+    let max_fee = BigInt::from_u32(&e, 10);
+    let args = (100u32, &min_fee, &max_fee, &admin2_id).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_fee"), args);
+    costs.push((
+        "set_fee",
+        measure(&e, || {
+            client.set_fee(&auth, &nonce, &None, &100, &min_fee, &max_fee, &admin2_id)
+        }),
+    ));
+
+    let args = (&user3_id, true).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_exempt"), args);
+    costs.push((
+        "set_exempt",
+        measure(&e, || {
+            client.set_exempt(&auth, &nonce, &None, &user3_id, &true)
+        }),
+    ));
+
+    let args = (&admin2_id,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_oracle"), args);
+    costs.push((
+        "set_oracle",
+        measure(&e, || client.set_oracle(&auth, &nonce, &None, &admin2_id)),
+    ));
+
+    let index = BigInt::from_u32(&e, 1_100_000_000);
+    let args = (&index,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("rebase"), args);
+    costs.push((
+        "rebase",
+        measure(&e, || client.rebase(&auth, &nonce, &None, &index)),
+    ));
+
+    let rate = BigInt::from_u32(&e, 1000);
+    let args = (&rate,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_rate"), args);
+    costs.push((
+        "set_rate",
+        measure(&e, || client.set_rate(&auth, &nonce, &None, &rate)),
+    ));
+
+    let args = (&name, &symbol).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_meta"), args);
+    costs.push((
+        "set_meta",
+        measure(&e, || client.set_meta(&auth, &nonce, &None, &name, &symbol)),
+    ));
+
+    let desc: Bytes = "description".into_val(&e);
+    let args = (MetaField::Desc, &desc).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_field"), args);
+    costs.push((
+        "set_field",
+        measure(&e, || {
+            client.set_field(&auth, &nonce, &None, &MetaField::Desc, &desc)
+        }),
+    ));
+
+    let compliance: Option<BytesN<32>> = None;
+    let args = (&compliance,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_comply"), args);
+    costs.push((
+        "set_comply",
+        measure(&e, || client.set_comply(&auth, &nonce, &None, &compliance)),
+    ));
+
+    let max = Some(BigInt::from_u32(&e, 1_000_000));
+    let args = (&max,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_max"), args);
+    costs.push((
+        "set_max",
+        measure(&e, || client.set_max(&auth, &nonce, &None, &max)),
+    ));
+
+    let args = (&user1_id, &max).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_max_id"), args);
+    costs.push((
+        "set_max_id",
+        measure(&e, || {
+            client.set_max_id(&auth, &nonce, &None, &user1_id, &max)
+        }),
+    ));
+
+    let args = (&user2_id, true).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_max_ex"), args);
+    costs.push((
+        "set_max_ex",
+        measure(&e, || {
+            client.set_max_ex(&auth, &nonce, &None, &user2_id, &true)
+        }),
+    ));
+
+    let limit = Some(VelocityLimit {
+        amount: BigInt::from_u32(&e, 1000),
+        window: 3600,
+    });
+    let args = (&user1_id, &limit).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_limit"), args);
+    costs.push((
+        "set_limit",
+        measure(&e, || {
+            client.set_limit(&auth, &nonce, &None, &user1_id, &limit)
+        }),
+    ));
+
+    let up_to = BigInt::from_u32(&e, 10);
+    let args = (&up_to,).into_val(&e);
+    let (auth, nonce) = token.sign(&user3, symbol!("invalidate"), args);
+    costs.push((
+        "invalidate",
+        measure(&e, || client.invalidate(&auth, &nonce, &None, &up_to)),
+    ));
+
+    let (auth, nonce) = token.sign(&user1, symbol!("revoke_all"), soroban_sdk::Vec::new(&e));
+    costs.push((
+        "revoke_all",
+        measure(&e, || client.revoke_all(&auth, &nonce, &None)),
+    ));
+
+    let timelock = Some(Timelock {
+        delay: 10,
+        mint_max: BigInt::zero(&e),
+    });
+    let args = (&timelock,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("set_tlock"), args);
+    costs.push((
+        "set_tlock",
+        measure(&e, || client.set_tlock(&auth, &nonce, &None, &timelock)),
+    ));
+
+    let action = Action::SetRate(BigInt::zero(&e));
+    let args = (&action,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("queue"), args);
+    costs.push((
+        "queue",
+        measure(&e, || {
+            client.queue(&auth, &nonce, &None, &action);
+        }),
+    ));
+
+    let args = (&action,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("queue"), args);
+    client.queue(&auth, &nonce, &None, &action);
+    let args = (1u32,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("cancel"), args);
+    costs.push((
+        "cancel",
+        measure(&e, || client.cancel(&auth, &nonce, &None, &1)),
+    ));
+
+    set_ledger(&e, 2, 1010);
+    let args = (0u32,).into_val(&e);
+    let (auth, nonce) = token.sign(&admin2, symbol!("execute"), args);
+    costs.push((
+        "execute",
+        measure(&e, || client.execute(&auth, &nonce, &None, &0)),
+    ));
+
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+    let wrapper_client = TokenClient::new(&e, &BytesN::from_array(&e, &wrapper_id));
+    let wrapper_contract_id = Identifier::Contract(BytesN::from_array(&e, &wrapper_id));
+    wrapper.init_wrap(&admin1_id, &contract_id, "wrapped", "wsymbol");
+    let ten = BigInt::from_u32(&e, 10);
+    token.approve(&user1, &wrapper_contract_id, &ten);
+
+    let args = (&ten,).into_val(&e);
+    let (auth, nonce) = wrapper.sign(&user1, symbol!("deposit"), args);
+    costs.push((
+        "deposit",
+        measure(&e, || wrapper_client.deposit(&auth, &nonce, &None, &ten)),
+    ));
+
+    let args = (&one,).into_val(&e);
+    let (auth, nonce) = wrapper.sign(&user1, symbol!("withdraw"), args);
+    costs.push((
+        "withdraw",
+        measure(&e, || wrapper_client.withdraw(&auth, &nonce, &None, &one)),
+    ));
+
+    costs.push((
+        "balance",
+        measure(&e, || {
+            client.balance(&user1_id);
+        }),
+    ));
+    costs.push((
+        "allowance",
+        measure(&e, || {
+            client.allowance(&user1_id, &user3_id);
+        }),
+    ));
+    costs.push((
+        "nonce",
+        measure(&e, || {
+            client.nonce(&user1_id, &None);
+        }),
+    ));
+    costs.push((
+        "votes",
+        measure(&e, || {
+            client.votes(&user1_id);
+        }),
+    ));
+    costs.push((
+        "votes_at",
+        measure(&e, || {
+            client.votes_at(&user1_id, &1);
+        }),
+    ));
+    costs.push((
+        "quote_xfer",
+        measure(&e, || {
+            client.quote_xfer(&user1_id, &user2_id, &amount);
+        }),
+    ));
+    costs.push((
+        "holders",
+        measure(&e, || {
+            client.holders(&0, &10);
+        }),
+    ));
+    costs.push((
+        "allowances",
+        measure(&e, || {
+            client.allowances(&user1_id, &0, &10);
+        }),
+    ));
+    costs.push((
+        "freeze_log",
+        measure(&e, || {
+            client.freeze_log(&user3_id);
+        }),
+    ));
+    costs.push((
+        "metadata",
+        measure(&e, || {
+            client.metadata();
+        }),
+    ));
+
+    let table = format_table(&costs);
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("target").join("budget.txt"), &table).unwrap();
+    println!("{}", table);
+
+    let baseline_path = root.join("tests").join("budget.txt");
+    if std::env::var_os("BUDGET_RECORD").is_some() {
+        fs::write(&baseline_path, &table).unwrap();
+        return;
+    }
+
+    let baseline = parse_baseline(&fs::read_to_string(&baseline_path).unwrap());
+    let mut regressions = Vec::new();
+    for (name, cost) in costs.iter() {
+        match baseline.get(*name) {
+            Some(base) if exceeds(cost.cpu, base.cpu) || exceeds(cost.mem, base.mem) => {
+                regressions.push(format!("{}: {:?} exceeds baseline {:?}", name, cost, base));
+            }
+            Some(_) => {}
+            None => regressions.push(format!("{}: no baseline recorded", name)),
+        }
+    }
+    assert!(regressions.is_empty(), "{}", regressions.join("\n"));
+}
@@ -0,0 +1,176 @@
+use ed25519_dalek::Keypair;
+use rand::{thread_rng, RngCore};
+use soroban_auth::Identifier;
+use soroban_sdk::{contractimpl, BigInt, BytesN, Env};
+use soroban_token_contract::testutils::{
+    register_test_contract as register_token, to_ed25519, Token,
+};
+
+// Restricts any single transfer above 500 with code 7.
+pub struct Compliance;
+
+#[contractimpl]
+impl Compliance {
+    pub fn can_transfer(e: Env, _from: Identifier, _to: Identifier, amount: BigInt) -> u32 {
+        if amount > BigInt::from_u32(&e, 500) {
+            7
+        } else {
+            0
+        }
+    }
+}
+
+fn generate_contract_id() -> [u8; 32] {
+    let mut id: [u8; 32] = Default::default();
+    thread_rng().fill_bytes(&mut id);
+    id
+}
+
+fn generate_keypair() -> Keypair {
+    Keypair::generate(&mut thread_rng())
+}
+
+fn register_compliance(e: &Env) -> [u8; 32] {
+    let id = generate_contract_id();
+    e.register_contract(&BytesN::from_array(e, &id), Compliance);
+    id
+}
+
+#[test]
+fn compliance() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let compliance_id = register_compliance(&e);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(
+        token.check_xfer(&user1_id, &user2_id, &BigInt::from_u32(&e, 600)),
+        0
+    );
+
+    token.set_comply(&admin1, Some(&compliance_id));
+    assert_eq!(
+        token.check_xfer(&user1_id, &user2_id, &BigInt::from_u32(&e, 600)),
+        7
+    );
+    assert_eq!(
+        token.check_xfer(&user1_id, &user2_id, &BigInt::from_u32(&e, 400)),
+        0
+    );
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1000));
+
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 400));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 400));
+
+    token.set_comply(&admin1, None);
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 600));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 1000));
+}
+
+#[test]
+#[should_panic(expected = "transfer restricted by compliance")]
+fn compliance_blocks_xfer() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let compliance_id = register_compliance(&e);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.set_comply(&admin1, Some(&compliance_id));
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 600));
+}
+
+#[test]
+#[should_panic(expected = "transfer restricted by compliance")]
+fn compliance_blocks_mint() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let compliance_id = register_compliance(&e);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.set_comply(&admin1, Some(&compliance_id));
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 600));
+}
+
+#[test]
+#[should_panic(expected = "transfer restricted by compliance")]
+fn compliance_blocks_relay_fee() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let compliance_id = register_compliance(&e);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.set_comply(&admin1, Some(&compliance_id));
+    token.xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 100),
+        &relayer_id,
+        &BigInt::from_u32(&e, 600),
+    );
+}
+
+#[test]
+#[should_panic(expected = "transfer restricted by compliance")]
+fn compliance_blocks_deposit() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+    let wrapper_contract_id = Identifier::Contract(BytesN::from_array(&e, &wrapper_id));
+    let compliance_id = register_compliance(&e);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+    wrapper.set_comply(&admin1, Some(&compliance_id));
+
+    underlying.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    underlying.approve(&user1, &wrapper_contract_id, &BigInt::from_u32(&e, 600));
+    wrapper.deposit(&user1, &BigInt::from_u32(&e, 600));
+}
@@ -0,0 +1,292 @@
+//! Runs random sequences of operations against the contract and a plain Rust
+//! model of the token, checking after every step that the two agree.
+//!
+//! Every operation is submitted, including those the model predicts would
+//! fail, and the contract must accept exactly the ones the model does.
+
+use ed25519_dalek::Keypair;
+use proptest::prelude::*;
+use rand::{thread_rng, RngCore};
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, Env};
+use soroban_token_contract::testutils::{
+    register_test_contract as register_token, to_ed25519, Token,
+};
+use soroban_token_contract::FreezeMode;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+const USERS: usize = 3;
+
+fn generate_contract_id() -> [u8; 32] {
+    let mut id: [u8; 32] = Default::default();
+    thread_rng().fill_bytes(&mut id);
+    id
+}
+
+fn generate_keypair() -> Keypair {
+    Keypair::generate(&mut thread_rng())
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Mint {
+        to: usize,
+        amount: i64,
+    },
+    Burn {
+        from: usize,
+        amount: i64,
+    },
+    Xfer {
+        from: usize,
+        to: usize,
+        amount: i64,
+    },
+    Approve {
+        from: usize,
+        spender: usize,
+        amount: i64,
+    },
+    XferFrom {
+        spender: usize,
+        from: usize,
+        to: usize,
+        amount: i64,
+    },
+    Freeze {
+        id: usize,
+        frozen: bool,
+    },
+}
+
+fn user() -> impl Strategy<Value = usize> {
+    0..USERS
+}
+
+fn amount() -> impl Strategy<Value = i64> {
+    prop_oneof![
+        Just(0i64),
+        -100..100i64,
+        any::<u32>().prop_map(i64::from),
+        any::<i32>().prop_map(i64::from),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (user(), amount()).prop_map(|(to, amount)| Op::Mint { to, amount }),
+        (user(), amount()).prop_map(|(from, amount)| Op::Burn { from, amount }),
+        (user(), user(), amount()).prop_map(|(from, to, amount)| Op::Xfer { from, to, amount }),
+        (user(), user(), amount()).prop_map(|(from, spender, amount)| Op::Approve {
+            from,
+            spender,
+            amount
+        }),
+        (user(), user(), user(), amount()).prop_map(|(spender, from, to, amount)| {
+            Op::XferFrom {
+                spender,
+                from,
+                to,
+                amount,
+            }
+        }),
+        (user(), any::<bool>()).prop_map(|(id, frozen)| Op::Freeze { id, frozen }),
+    ]
+}
+
+#[derive(Default)]
+struct Model {
+    balances: [u128; USERS],
+    allowances: [[i128; USERS]; USERS],
+    nonces: [u64; USERS],
+    admin_nonce: u64,
+    frozen: [bool; USERS],
+    minted: u128,
+    burned: u128,
+}
+
+impl Model {
+    // Applies `op` if it would succeed on the contract, returning whether it
+    // did.
+    fn apply(&mut self, op: &Op) -> bool {
+        match *op {
+            Op::Mint { to, amount } => {
+                if amount < 0 || self.frozen[to] {
+                    return false;
+                }
+                self.balances[to] += amount as u128;
+                self.minted += amount as u128;
+                self.admin_nonce += 1;
+            }
+            Op::Burn { from, amount } => {
+                if amount < 0 {
+                    return false;
+                }
+                let amount = amount as u128;
+                if self.frozen[from] || self.balances[from] < amount {
+                    return false;
+                }
+                self.balances[from] -= amount;
+                self.burned += amount;
+                self.admin_nonce += 1;
+            }
+            Op::Xfer { from, to, amount } => {
+                if amount < 0 {
+                    return false;
+                }
+                let amount = amount as u128;
+                if self.frozen[from] || self.frozen[to] || self.balances[from] < amount {
+                    return false;
+                }
+                self.balances[from] -= amount;
+                self.balances[to] += amount;
+                self.nonces[from] += 1;
+            }
+            Op::Approve {
+                from,
+                spender,
+                amount,
+            } => {
+                self.allowances[from][spender] = amount as i128;
+                self.nonces[from] += 1;
+            }
+            Op::XferFrom {
+                spender,
+                from,
+                to,
+                amount,
+            } => {
+                if amount < 0 || self.allowances[from][spender] < amount as i128 {
+                    return false;
+                }
+                let amount = amount as u128;
+                if self.frozen[from] || self.frozen[to] || self.balances[from] < amount {
+                    return false;
+                }
+                self.allowances[from][spender] -= amount as i128;
+                self.balances[from] -= amount;
+                self.balances[to] += amount;
+                self.nonces[spender] += 1;
+            }
+            Op::Freeze { id, frozen } => {
+                self.frozen[id] = frozen;
+                self.admin_nonce += 1;
+            }
+        }
+        true
+    }
+}
+
+fn big(e: &Env, n: i128) -> BigInt {
+    let abs = n.unsigned_abs();
+    let high = BigInt::from_u64(e, (abs >> 64) as u64);
+    let low = BigInt::from_u64(e, abs as u64);
+    let abs = high * (BigInt::from_u64(e, u64::MAX) + BigInt::from_u32(e, 1)) + low;
+    if n < 0 {
+        BigInt::zero(e) - abs
+    } else {
+        abs
+    }
+}
+
+fn submit(e: &Env, token: &Token, admin: &Keypair, users: &[Keypair], ids: &[Identifier], op: &Op) {
+    match *op {
+        Op::Mint { to, amount } => {
+            token.mint(admin, &ids[to], &big(e, amount as i128));
+        }
+        Op::Burn { from, amount } => {
+            token.burn(admin, &ids[from], &big(e, amount as i128));
+        }
+        Op::Xfer { from, to, amount } => {
+            token.xfer(&users[from], &ids[to], &big(e, amount as i128));
+        }
+        Op::Approve {
+            from,
+            spender,
+            amount,
+        } => {
+            token.approve(&users[from], &ids[spender], &big(e, amount as i128));
+        }
+        Op::XferFrom {
+            spender,
+            from,
+            to,
+            amount,
+        } => {
+            token.xfer_from(
+                &users[spender],
+                &ids[from],
+                &ids[to],
+                &big(e, amount as i128),
+            );
+        }
+        Op::Freeze { id, frozen } => {
+            if frozen {
+                token.freeze(admin, &ids[id]);
+            } else {
+                token.unfreeze(admin, &ids[id]);
+            }
+        }
+    }
+}
+
+fn run(ops: Vec<Op>) {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin = generate_keypair();
+    let admin_id = to_ed25519(&e, &admin);
+    let users: Vec<Keypair> = (0..USERS).map(|_| generate_keypair()).collect();
+    let ids: Vec<Identifier> = users.iter().map(|kp| to_ed25519(&e, kp)).collect();
+
+    token.initialize(&admin_id, 7, "name", "symbol");
+
+    let mut model = Model::default();
+    for op in ops.iter() {
+        let accepted = model.apply(op);
+        let submitted = catch_unwind(AssertUnwindSafe(|| {
+            submit(&e, &token, &admin, &users, &ids, op)
+        }))
+        .is_ok();
+        assert_eq!(submitted, accepted, "{:?}", op);
+
+        // A rejected call is rolled back, so it leaves every nonce unchanged
+        // along with everything else.
+        let mut total = BigInt::zero(&e);
+        for (i, id) in ids.iter().enumerate() {
+            let balance = token.balance(id);
+            assert_eq!(balance, big(&e, model.balances[i] as i128));
+            total = total + balance;
+            for (j, spender) in ids.iter().enumerate() {
+                assert_eq!(
+                    token.allowance(id, spender),
+                    big(&e, model.allowances[i][j])
+                );
+            }
+            assert_eq!(token.nonce(id), BigInt::from_u64(&e, model.nonces[i]));
+            let mode = if model.frozen[i] {
+                FreezeMode::Full
+            } else {
+                FreezeMode::Off
+            };
+            assert_eq!(token.is_frozen(id), mode);
+        }
+        assert_eq!(
+            token.nonce(&admin_id),
+            BigInt::from_u64(&e, model.admin_nonce)
+        );
+        assert_eq!(total, big(&e, (model.minted - model.burned) as i128));
+        assert_eq!(token.supply(), total);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn matches_model(ops in proptest::collection::vec(op(), 1..40)) {
+        run(ops);
+    }
+}
@@ -1,11 +1,17 @@
 use ed25519_dalek::Keypair;
 use rand::{thread_rng, RngCore};
-use soroban_auth::{Ed25519Signature, Signature};
-use soroban_sdk::{BigInt, BytesN, Env, IntoVal};
+use soroban_auth::{Ed25519Signature, Identifier, Signature};
+use soroban_sdk::testutils::{Accounts, Ledger, LedgerInfo};
+use soroban_sdk::{symbol, BigInt, Bytes, BytesN, Env, IntoVal, Vec};
 use soroban_token_contract::testutils::{
-    register_test_contract as register_token, to_ed25519, Token,
+    register_test_contract as register_token, to_ed25519, AccountSigner, InvokerSigner, TestSigner,
+    Token,
 };
-use soroban_token_contract::TokenClient;
+use soroban_token_contract::{
+    Action, Approval, BurnAction, FreezeMode, FreezeRecord, MetaField, MintAction, QueuedAction,
+    Timelock, TokenClient, VelocityLimit,
+};
+use std::panic::{catch_unwind, AssertUnwindSafe};
 
 fn generate_contract_id() -> [u8; 32] {
     let mut id: [u8; 32] = Default::default();
@@ -17,66 +23,140 @@ fn generate_keypair() -> Keypair {
     Keypair::generate(&mut thread_rng())
 }
 
-#[test]
-fn test() {
+// An account with two signers, both of which are needed to meet its
+// thresholds.
+fn generate_account(e: &Env) -> AccountSigner {
+    let signers = vec![generate_keypair(), generate_keypair()];
+    let account_id = e.accounts().generate();
+    e.accounts().create(&account_id);
+    e.accounts()
+        .update(&account_id)
+        .master_weight(0)
+        .signer(&signers[0].public.to_bytes(), 1)
+        .signer(&signers[1].public.to_bytes(), 1)
+        .thresholds(2, 2, 2);
+    AccountSigner {
+        account_id,
+        signers,
+    }
+}
+
+fn generate_invoker(e: &Env) -> InvokerSigner {
+    let account_id = e.accounts().generate();
+    e.accounts().create(&account_id);
+    InvokerSigner { account_id }
+}
+
+// Invoker authorization doesn't consume nonces, so theirs stay at zero.
+fn expected_nonce(e: &Env, signer: &impl TestSigner, used: u32) -> BigInt {
+    if signer.uses_nonce() {
+        BigInt::from_u32(e, used)
+    } else {
+        BigInt::zero(e)
+    }
+}
+
+fn lane_nonce(e: &Env, lane: u64, sequence: u32) -> BigInt {
+    BigInt::from_u64(e, lane) * (BigInt::from_u64(e, u64::MAX) + BigInt::from_u32(e, 1))
+        + BigInt::from_u32(e, sequence)
+}
+
+fn bytes(e: &Env, s: &str) -> Bytes {
+    s.into_val(e)
+}
+
+fn identifiers(e: &Env, ids: &[Identifier]) -> Vec<Identifier> {
+    let mut v = Vec::new(e);
+    for id in ids {
+        v.push_back(id.clone());
+    }
+    v
+}
+
+fn set_ledger(e: &Env, sequence_number: u32, timestamp: u64) {
+    e.ledger().set(LedgerInfo {
+        protocol_version: 1,
+        sequence_number,
+        timestamp,
+        network_passphrase: vec![0],
+        base_reserve: 10,
+    });
+}
+
+fn scenario<S: TestSigner>(generate_signer: impl Fn(&Env) -> S) {
     let e: Env = Default::default();
     let contract_id = generate_contract_id();
     register_token(&e, &contract_id);
     let token = Token::new(&e, &contract_id);
 
-    let admin1 = generate_keypair();
-    let admin1_id = to_ed25519(&e, &admin1);
-    let admin2 = generate_keypair();
-    let admin2_id = to_ed25519(&e, &admin2);
-    let user1 = generate_keypair();
-    let user1_id = to_ed25519(&e, &user1);
-    let user2 = generate_keypair();
-    let user2_id = to_ed25519(&e, &user2);
-    let user3 = generate_keypair();
-    let user3_id = to_ed25519(&e, &user3);
+    let admin1 = generate_signer(&e);
+    let admin1_id = admin1.identifier(&e);
+    let admin2 = generate_signer(&e);
+    let admin2_id = admin2.identifier(&e);
+    let user1 = generate_signer(&e);
+    let user1_id = user1.identifier(&e);
+    let user2 = generate_signer(&e);
+    let user2_id = user2.identifier(&e);
+    let user3 = generate_signer(&e);
+    let user3_id = user3.identifier(&e);
 
     token.initialize(&admin1_id, 7, "name", "symbol");
 
     token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
     assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1000));
-    assert_eq!(token.nonce(&admin1_id), BigInt::from_u32(&e, 1));
+    assert_eq!(token.nonce(&admin1_id), expected_nonce(&e, &admin1, 1));
 
     token.approve(&user2, &user3_id, &BigInt::from_u32(&e, 500));
     assert_eq!(
         token.allowance(&user2_id, &user3_id),
         BigInt::from_u32(&e, 500)
     );
-    assert_eq!(token.nonce(&user2_id), BigInt::from_u32(&e, 1));
+    assert_eq!(token.nonce(&user2_id), expected_nonce(&e, &user2, 1));
 
     token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 600));
     assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 400));
     assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 600));
-    assert_eq!(token.nonce(&user1_id), BigInt::from_u32(&e, 1));
+    assert_eq!(token.nonce(&user1_id), expected_nonce(&e, &user1, 1));
 
     token.xfer_from(&user3, &user2_id, &user1_id, &BigInt::from_u32(&e, 400));
     assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 800));
     assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 200));
-    assert_eq!(token.nonce(&user3_id), BigInt::from_u32(&e, 1));
+    assert_eq!(token.nonce(&user3_id), expected_nonce(&e, &user3, 1));
 
     token.xfer(&user1, &user3_id, &BigInt::from_u32(&e, 300));
     assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 500));
     assert_eq!(token.balance(&user3_id), BigInt::from_u32(&e, 300));
-    assert_eq!(token.nonce(&user1_id), BigInt::from_u32(&e, 2));
+    assert_eq!(token.nonce(&user1_id), expected_nonce(&e, &user1, 2));
 
     token.set_admin(&admin1, &admin2_id);
-    assert_eq!(token.nonce(&admin1_id), BigInt::from_u32(&e, 2));
+    assert_eq!(token.nonce(&admin1_id), expected_nonce(&e, &admin1, 2));
 
     token.freeze(&admin2, &user2_id);
-    assert_eq!(token.is_frozen(&user2_id), true);
-    assert_eq!(token.nonce(&admin2_id), BigInt::from_u32(&e, 1));
+    assert_eq!(token.is_frozen(&user2_id), FreezeMode::Full);
+    assert_eq!(token.nonce(&admin2_id), expected_nonce(&e, &admin2, 1));
 
     token.unfreeze(&admin2, &user3_id);
-    assert_eq!(token.is_frozen(&user3_id), false);
-    assert_eq!(token.nonce(&admin2_id), BigInt::from_u32(&e, 2));
+    assert_eq!(token.is_frozen(&user3_id), FreezeMode::Off);
+    assert_eq!(token.nonce(&admin2_id), expected_nonce(&e, &admin2, 2));
 
     token.burn(&admin2, &user3_id, &BigInt::from_u32(&e, 100));
     assert_eq!(token.balance(&user3_id), BigInt::from_u32(&e, 200));
-    assert_eq!(token.nonce(&admin2_id), BigInt::from_u32(&e, 3));
+    assert_eq!(token.nonce(&admin2_id), expected_nonce(&e, &admin2, 3));
+}
+
+#[test]
+fn test() {
+    scenario(|_| generate_keypair());
+}
+
+#[test]
+fn test_account() {
+    scenario(generate_account);
+}
+
+#[test]
+fn test_invoker() {
+    scenario(generate_invoker);
 }
 
 #[test]
@@ -227,8 +307,8 @@ fn set_admin_bad_signature() {
     let contract_id_bin = BytesN::from_array(&e, &contract_id);
 
     let client = TokenClient::new(&e, &contract_id_bin);
-    let nonce = client.nonce(&admin1_id);
-    client.set_admin(&auth, &nonce, &admin2_id);
+    let nonce = client.nonce(&admin1_id, &None);
+    client.set_admin(&auth, &nonce, &None, &admin2_id);
 }
 
 #[test]
@@ -244,3 +324,1760 @@ fn decimal_is_over_max() {
 
     token.initialize(&admin1_id, u32::from(u8::MAX) + 1, "name", "symbol");
 }
+
+#[test]
+fn delegate_votes() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let user3 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let user3_id = to_ed25519(&e, &user3);
+
+    set_ledger(&e, 1, 1);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.votes(&user2_id), BigInt::zero(&e));
+
+    set_ledger(&e, 2, 2);
+    token.delegate(&user1, &user2_id);
+    assert_eq!(token.votes(&user2_id), BigInt::from_u32(&e, 1000));
+    assert_eq!(token.nonce(&user1_id), BigInt::from_u32(&e, 1));
+
+    set_ledger(&e, 3, 3);
+    token.xfer(&user1, &user3_id, &BigInt::from_u32(&e, 400));
+    assert_eq!(token.votes(&user2_id), BigInt::from_u32(&e, 600));
+
+    set_ledger(&e, 4, 4);
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 100));
+    token.burn(&admin1, &user1_id, &BigInt::from_u32(&e, 50));
+    assert_eq!(token.votes(&user2_id), BigInt::from_u32(&e, 650));
+
+    set_ledger(&e, 5, 5);
+    token.delegate(&user1, &user3_id);
+    assert_eq!(token.votes(&user2_id), BigInt::zero(&e));
+    assert_eq!(token.votes(&user3_id), BigInt::from_u32(&e, 650));
+
+    assert_eq!(token.votes_at(&user2_id, 1), BigInt::zero(&e));
+    assert_eq!(token.votes_at(&user2_id, 2), BigInt::from_u32(&e, 1000));
+    assert_eq!(token.votes_at(&user2_id, 3), BigInt::from_u32(&e, 600));
+    assert_eq!(token.votes_at(&user2_id, 4), BigInt::from_u32(&e, 650));
+    assert_eq!(token.votes_at(&user2_id, 5), BigInt::zero(&e));
+    assert_eq!(token.votes_at(&user3_id, 4), BigInt::zero(&e));
+}
+
+#[test]
+fn votes_at_many_checkpoints() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    set_ledger(&e, 1, 1);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.delegate(&user1, &user2_id);
+
+    // One checkpoint on every even ledger from 2 to 40.
+    for i in 1..=20 {
+        set_ledger(&e, 2 * i, 2 * i as u64);
+        token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 10));
+    }
+
+    assert_eq!(token.votes_at(&user2_id, 1), BigInt::zero(&e));
+    for i in 1..=20 {
+        let votes = BigInt::from_u32(&e, 10 * i);
+        assert_eq!(token.votes_at(&user2_id, 2 * i), votes);
+        assert_eq!(token.votes_at(&user2_id, 2 * i + 1), votes);
+    }
+    assert_eq!(token.votes_at(&user2_id, 1000), BigInt::from_u32(&e, 200));
+}
+
+#[test]
+fn xfer_relay() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    let signed = token.sign_xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 600),
+        &relayer_id,
+        &BigInt::from_u32(&e, 10),
+    );
+    token.submit_xfer_relay(&signed);
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 390));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 600));
+    assert_eq!(token.balance(&relayer_id), BigInt::from_u32(&e, 10));
+    assert_eq!(token.nonce(&user1_id), BigInt::from_u32(&e, 1));
+}
+
+#[test]
+#[should_panic(expected = "insufficient balance")]
+fn xfer_relay_insufficient_balance_for_fee() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    token.xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 1000),
+        &relayer_id,
+        &BigInt::from_u32(&e, 1),
+    );
+}
+
+#[test]
+#[should_panic(expected = "fee must not be negative")]
+fn xfer_relay_negative_fee() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &relayer_id, &BigInt::from_u32(&e, 1000));
+
+    // A negative fee would move tokens from the relayer to the signer.
+    token.xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::zero(&e),
+        &relayer_id,
+        &(BigInt::zero(&e) - BigInt::from_u32(&e, 500)),
+    );
+}
+
+#[test]
+#[should_panic(expected = "amount must not be negative")]
+fn xfer_relay_negative_amount() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.mint(&admin1, &user2_id, &BigInt::from_u32(&e, 1000));
+
+    // A negative amount would move tokens from the recipient to the signer.
+    token.xfer_relay(
+        &user1,
+        &user2_id,
+        &(BigInt::zero(&e) - BigInt::from_u32(&e, 500)),
+        &relayer_id,
+        &BigInt::zero(&e),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Failed ED25519 verification")]
+fn xfer_relay_fee_not_signed() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    let mut signed = token.sign_xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 600),
+        &relayer_id,
+        &BigInt::from_u32(&e, 10),
+    );
+    signed.fee = BigInt::from_u32(&e, 100);
+    token.submit_xfer_relay(&signed);
+}
+
+#[test]
+fn xfer_fee() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let user3 = generate_keypair();
+    let collector = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let user3_id = to_ed25519(&e, &user3);
+    let collector_id = to_ed25519(&e, &collector);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 100000));
+
+    // 1% fee, at least 5 and at most 200.
+    token.set_fee(
+        &admin1,
+        100,
+        &BigInt::from_u32(&e, 5),
+        &BigInt::from_u32(&e, 200),
+        &collector_id,
+    );
+
+    let quote = token.quote_xfer(&user1_id, &user2_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(quote.net, BigInt::from_u32(&e, 990));
+    assert_eq!(quote.fee, BigInt::from_u32(&e, 10));
+
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 99000));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 990));
+    assert_eq!(token.balance(&collector_id), BigInt::from_u32(&e, 10));
+
+    // Minimum fee.
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 100));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 1085));
+    assert_eq!(token.balance(&collector_id), BigInt::from_u32(&e, 15));
+
+    // Maximum fee.
+    token.approve(&user1, &user3_id, &BigInt::from_u32(&e, 50000));
+    token.xfer_from(&user3, &user1_id, &user2_id, &BigInt::from_u32(&e, 50000));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 48900));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 50885));
+    assert_eq!(token.balance(&collector_id), BigInt::from_u32(&e, 215));
+
+    // Exempt accounts pay no fee, whether sending or receiving.
+    token.set_exempt(&admin1, &user2_id, true);
+    let quote = token.quote_xfer(&user1_id, &user2_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(quote.net, BigInt::from_u32(&e, 1000));
+    assert_eq!(quote.fee, BigInt::zero(&e));
+    token.xfer(&user2, &user3_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.balance(&user3_id), BigInt::from_u32(&e, 1000));
+    assert_eq!(token.balance(&collector_id), BigInt::from_u32(&e, 215));
+}
+
+#[test]
+#[should_panic(expected = "amount must not be negative")]
+fn xfer_negative_with_fee() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let collector = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let collector_id = to_ed25519(&e, &collector);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.mint(&admin1, &user2_id, &BigInt::from_u32(&e, 1000));
+    token.set_fee(
+        &admin1,
+        100,
+        &BigInt::from_u32(&e, 5),
+        &BigInt::from_u32(&e, 200),
+        &collector_id,
+    );
+
+    // With a fee, a negative amount would credit the collector out of thin air.
+    token.xfer(
+        &user1,
+        &user2_id,
+        &(BigInt::zero(&e) - BigInt::from_u32(&e, 500)),
+    );
+}
+
+#[test]
+#[should_panic(expected = "max_fee must not be less than min_fee")]
+fn set_fee_max_below_min() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let collector = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let collector_id = to_ed25519(&e, &collector);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.set_fee(
+        &admin1,
+        100,
+        &BigInt::from_u32(&e, 10),
+        &BigInt::from_u32(&e, 5),
+        &collector_id,
+    );
+}
+
+#[test]
+fn rebase() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let oracle = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let oracle_id = to_ed25519(&e, &oracle);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    assert_eq!(token.index(), BigInt::from_u32(&e, 1_000_000_000));
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.shares_of(&user1_id), BigInt::from_u32(&e, 1000));
+
+    token.rebase(&admin1, &BigInt::from_u32(&e, 2_000_000_000));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 2000));
+    assert_eq!(token.shares_of(&user1_id), BigInt::from_u32(&e, 1000));
+
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 500));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1500));
+    assert_eq!(token.shares_of(&user1_id), BigInt::from_u32(&e, 750));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 500));
+    assert_eq!(token.shares_of(&user2_id), BigInt::from_u32(&e, 250));
+
+    token.set_oracle(&admin1, &oracle_id);
+    token.rebase(&oracle, &BigInt::from_u32(&e, 500_000_000));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 375));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 125));
+}
+
+#[test]
+fn votes_after_rebase() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    set_ledger(&e, 1, 1);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.delegate(&user1, &user2_id);
+
+    // Votes are in shares, so they don't follow the balance through a rebase.
+    set_ledger(&e, 2, 2);
+    token.rebase(&admin1, &BigInt::from_u32(&e, 2_000_000_000));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 2000));
+    assert_eq!(token.votes(&user2_id), BigInt::from_u32(&e, 1000));
+    assert_eq!(token.votes(&user2_id), token.shares_of(&user1_id));
+
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.votes(&user2_id), BigInt::from_u32(&e, 1500));
+    assert_eq!(token.votes_at(&user2_id, 1), BigInt::from_u32(&e, 1000));
+}
+
+#[test]
+fn rebase_rounding() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.rebase(&admin1, &BigInt::from_u32(&e, 1_500_000_000));
+
+    // Credits round down: 100 / 1.5 = 66.67 shares, worth 99 units.
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 100));
+    assert_eq!(token.shares_of(&user1_id), BigInt::from_u32(&e, 66));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 99));
+
+    // Debits round up: 10 / 1.5 = 6.67 shares, so 7 shares are debited while
+    // only 6 are credited.
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 10));
+    assert_eq!(token.shares_of(&user1_id), BigInt::from_u32(&e, 59));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 88));
+    assert_eq!(token.shares_of(&user2_id), BigInt::from_u32(&e, 6));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 9));
+
+    // The share that was debited but not credited leaves the supply.
+    assert_eq!(token.supply(), BigInt::from_u32(&e, 97));
+
+    // A holder can always spend their whole displayed balance.
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 88));
+    assert_eq!(token.shares_of(&user1_id), BigInt::zero(&e));
+    assert_eq!(token.balance(&user1_id), BigInt::zero(&e));
+    assert_eq!(token.supply(), token.balance(&user2_id));
+}
+
+#[test]
+#[should_panic(expected = "not authorized by admin or oracle")]
+fn rebase_not_authorized() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.rebase(&user1, &BigInt::from_u32(&e, 2_000_000_000));
+}
+
+#[test]
+fn interest_accrual() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    set_ledger(&e, 1, 1000);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1_000_000));
+    assert_eq!(token.supply(), BigInt::from_u32(&e, 1_000_000));
+
+    // 0.0001% per second.
+    token.set_rate(&admin1, &BigInt::from_u64(&e, 1_000_000_000_000));
+
+    set_ledger(&e, 2, 2000);
+    assert_eq!(token.accrued(&user1_id), BigInt::from_u32(&e, 1000));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1_001_000));
+    // Interest only counts toward supply once it has been minted.
+    assert_eq!(token.supply(), BigInt::from_u32(&e, 1_000_000));
+
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.accrued(&user1_id), BigInt::zero(&e));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1_000_000));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 1000));
+    assert_eq!(token.supply(), BigInt::from_u32(&e, 1_001_000));
+
+    // Stopping interest settles the index at the old rate.
+    set_ledger(&e, 3, 3000);
+    token.set_rate(&admin1, &BigInt::zero(&e));
+    set_ledger(&e, 4, 10000);
+    assert_eq!(token.accrued(&user2_id), BigInt::from_u32(&e, 1));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 1001));
+}
+
+#[test]
+fn metadata() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let metadata = token.metadata();
+    assert_eq!(metadata.name, bytes(&e, "name"));
+    assert_eq!(metadata.symbol, bytes(&e, "symbol"));
+    assert_eq!(metadata.decimals, 7);
+    assert_eq!(metadata.icon_uri, Bytes::new(&e));
+
+    token.set_meta(&admin1, "new name", "NEW");
+    assert_eq!(token.name(), bytes(&e, "new name"));
+    assert_eq!(token.symbol(), bytes(&e, "NEW"));
+
+    token.set_field(&admin1, MetaField::IconUri, "https://example.com/icon.png");
+    token.set_field(&admin1, MetaField::HomeDomain, "example.com");
+    token.set_field(&admin1, MetaField::Desc, "An example token");
+    token.set_field(&admin1, MetaField::IssuerUrl, "https://example.com");
+    assert_eq!(token.nonce(&admin1_id), BigInt::from_u32(&e, 5));
+
+    let metadata = token.metadata();
+    assert_eq!(metadata.name, bytes(&e, "new name"));
+    assert_eq!(metadata.symbol, bytes(&e, "NEW"));
+    assert_eq!(metadata.icon_uri, bytes(&e, "https://example.com/icon.png"));
+    assert_eq!(metadata.domain, bytes(&e, "example.com"));
+    assert_eq!(metadata.desc, bytes(&e, "An example token"));
+    assert_eq!(metadata.issuer_url, bytes(&e, "https://example.com"));
+}
+
+#[test]
+#[should_panic(expected = "not authorized by admin")]
+fn set_meta_not_admin() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.set_meta(&user1, "new name", "NEW");
+}
+
+#[test]
+#[should_panic(expected = "decimal must not exceed 18")]
+fn decimal_is_over_cap() {
+    let e = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 19, "name", "symbol");
+}
+
+#[test]
+#[should_panic(expected = "name must not be empty")]
+fn name_is_empty() {
+    let e = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "", "symbol");
+}
+
+#[test]
+#[should_panic(expected = "name must not exceed 64 bytes")]
+fn name_is_too_long() {
+    let e = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, &"n".repeat(65), "symbol");
+}
+
+#[test]
+#[should_panic(expected = "name must be printable ASCII")]
+fn name_is_not_ascii() {
+    let e = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "nämé", "symbol");
+}
+
+#[test]
+#[should_panic(expected = "symbol must not be empty")]
+fn symbol_is_empty() {
+    let e = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "");
+}
+
+#[test]
+#[should_panic(expected = "symbol must not exceed 12 bytes")]
+fn symbol_is_too_long() {
+    let e = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "SYMBOLSYMBOLS");
+}
+
+#[test]
+#[should_panic(expected = "symbol must be printable ASCII without spaces")]
+fn symbol_has_space() {
+    let e = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "SYM BOL");
+}
+
+#[test]
+#[should_panic(expected = "symbol must not be empty")]
+fn set_meta_symbol_is_empty() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.set_meta(&admin1, "name", "");
+}
+
+#[test]
+fn migrate_from_unversioned() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    // Seed the layout written by instances deployed before versioning.
+    let contract_id_bin = BytesN::from_array(&e, &contract_id);
+    e.as_contract(&contract_id_bin, || {
+        let data = e.contract_data();
+        data.set((symbol!("Admin"),), admin1_id.clone());
+        data.set((symbol!("Decimals"),), 7u32);
+        data.set((symbol!("Name"),), bytes(&e, "name"));
+        data.set((symbol!("Symbol"),), bytes(&e, "symbol"));
+        data.set(
+            (symbol!("Balance"), user1_id.clone()),
+            BigInt::from_u32(&e, 1000),
+        );
+        data.set(
+            (symbol!("Nonce"), admin1_id.clone()),
+            BigInt::from_u32(&e, 3),
+        );
+        data.set((symbol!("State"), user2_id.clone()), true);
+    });
+    assert_eq!(token.version(), 0);
+
+    token.migrate(&admin1);
+    assert_eq!(token.version(), 1);
+    assert_eq!(token.nonce(&admin1_id), BigInt::from_u32(&e, 4));
+    assert_eq!(token.decimals(), 7);
+    assert_eq!(token.name(), bytes(&e, "name"));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1000));
+    assert_eq!(token.is_frozen(&user2_id), FreezeMode::Full);
+
+    token.xfer(&user1, &admin1_id, &BigInt::from_u32(&e, 400));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 600));
+
+    // Per-account entries are upgraded when next written.
+    token.unfreeze(&admin1, &user2_id);
+    assert_eq!(token.is_frozen(&user2_id), FreezeMode::Off);
+    e.as_contract(&contract_id_bin, || {
+        assert!(!e.contract_data().has((symbol!("State"), user2_id.clone())));
+    });
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 100));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 100));
+    assert_eq!(token.holder_cnt(), 3);
+}
+
+#[test]
+#[should_panic(expected = "already at latest version")]
+fn migrate_latest_version() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    assert_eq!(token.version(), 1);
+    token.migrate(&admin1);
+}
+
+#[test]
+fn nonce_lanes() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let lane1 = token.with_lane(1);
+    let lane2 = token.with_lane(2);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    // Transfers signed in different lanes can be submitted in any order.
+    let signed1 = lane1.sign_xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 100),
+        &relayer_id,
+        &BigInt::zero(&e),
+    );
+    let signed2 = lane2.sign_xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 200),
+        &relayer_id,
+        &BigInt::zero(&e),
+    );
+    assert_eq!(signed1.nonce, lane_nonce(&e, 1, 0));
+    assert_eq!(signed2.nonce, lane_nonce(&e, 2, 0));
+    token.submit_xfer_relay(&signed2);
+    token.submit_xfer_relay(&signed1);
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 300));
+
+    assert_eq!(token.nonce(&user1_id), BigInt::zero(&e));
+    assert_eq!(lane1.nonce(&user1_id), lane_nonce(&e, 1, 1));
+    assert_eq!(lane2.nonce(&user1_id), lane_nonce(&e, 2, 1));
+
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 300));
+    assert_eq!(token.nonce(&user1_id), BigInt::from_u32(&e, 1));
+    assert_eq!(lane1.nonce(&user1_id), lane_nonce(&e, 1, 1));
+}
+
+#[test]
+fn invalidate_nonces() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let lane1 = token.with_lane(1);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    // The call itself is signed on lane 0, which only advances by one.
+    token.invalidate(&user1, &lane_nonce(&e, 1, 5));
+    assert_eq!(token.nonce(&user1_id), BigInt::from_u32(&e, 1));
+    assert_eq!(lane1.nonce(&user1_id), lane_nonce(&e, 1, 5));
+}
+
+#[test]
+#[should_panic(expected = "incorrect nonce")]
+fn invalidate_cancels_signed() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let lane1 = token.with_lane(1);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    let signed = lane1.sign_xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 100),
+        &relayer_id,
+        &BigInt::zero(&e),
+    );
+
+    // Cancel the pre-signed transfer from lane 0.
+    token.invalidate(&user1, &lane_nonce(&e, 1, 5));
+
+    token.submit_xfer_relay(&signed);
+}
+
+#[test]
+#[should_panic(expected = "nonces already used")]
+fn invalidate_used_nonces() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.invalidate(&user1, &BigInt::from_u32(&e, 1));
+}
+
+#[test]
+#[should_panic(expected = "nonce out of range")]
+fn invalidate_negative_nonce() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.invalidate(&user1, &(BigInt::zero(&e) - BigInt::from_u32(&e, 1)));
+}
+
+#[test]
+#[should_panic(expected = "nonce lane exhausted")]
+fn invalidate_to_lane_max() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+    let lane1 = token.with_lane(1);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    // Move lane 1 to its last usable nonce.
+    let last = lane_nonce(&e, 1, 0) + BigInt::from_u64(&e, u64::MAX - 1);
+    token.invalidate(&user1, &last);
+    assert_eq!(lane1.nonce(&user1_id), last);
+
+    let signed = lane1.sign_xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 100),
+        &relayer_id,
+        &BigInt::zero(&e),
+    );
+    assert_eq!(signed.nonce, last);
+    token.submit_xfer_relay(&signed);
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 100));
+
+    // The lane is used up rather than wrapping into the next one, so the
+    // transfer can't be replayed.
+    token.submit_xfer_relay(&signed);
+}
+
+#[test]
+#[should_panic(expected = "nonce out of range")]
+fn invalidate_last_slot() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let up_to = lane_nonce(&e, 1, 0) + BigInt::from_u64(&e, u64::MAX);
+    token.invalidate(&user1, &up_to);
+}
+
+#[test]
+fn valid_until() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id).with_valid_until(Some(10));
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    set_ledger(&e, 10, 10);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 400));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 400));
+}
+
+#[test]
+#[should_panic(expected = "signature expired")]
+fn valid_until_expired() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    set_ledger(&e, 10, 10);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    let signed = token.with_valid_until(Some(10)).sign_xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 400),
+        &relayer_id,
+        &BigInt::zero(&e),
+    );
+    set_ledger(&e, 11, 11);
+    token.submit_xfer_relay(&signed);
+}
+
+#[test]
+#[should_panic(expected = "Failed ED25519 verification")]
+fn valid_until_not_signed() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let relayer = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let relayer_id = to_ed25519(&e, &relayer);
+
+    set_ledger(&e, 10, 10);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+
+    let mut signed = token.with_valid_until(Some(10)).sign_xfer_relay(
+        &user1,
+        &user2_id,
+        &BigInt::from_u32(&e, 400),
+        &relayer_id,
+        &BigInt::zero(&e),
+    );
+    signed.valid_until = Some(20);
+    set_ledger(&e, 11, 11);
+    token.submit_xfer_relay(&signed);
+}
+
+#[test]
+fn holders() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let user3 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let user3_id = to_ed25519(&e, &user3);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    assert_eq!(token.holder_cnt(), 0);
+
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.mint(&admin1, &user2_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.holder_cnt(), 2);
+    assert_eq!(
+        token.holders(0, 10),
+        identifiers(&e, &[user1_id.clone(), user2_id.clone()])
+    );
+
+    // Emptying user1's balance moves the last holder into its slot.
+    token.xfer(&user1, &user3_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.holder_cnt(), 2);
+    assert_eq!(
+        token.holders(0, 10),
+        identifiers(&e, &[user2_id.clone(), user3_id.clone()])
+    );
+    assert_eq!(token.holders(1, 1), identifiers(&e, &[user3_id.clone()]));
+    assert_eq!(token.holders(2, 10), identifiers(&e, &[]));
+
+    token.burn(&admin1, &user2_id, &BigInt::from_u32(&e, 1000));
+    assert_eq!(token.holder_cnt(), 1);
+    assert_eq!(token.holders(0, 10), identifiers(&e, &[user3_id]));
+}
+
+#[test]
+fn allowances() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let user3 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let user3_id = to_ed25519(&e, &user3);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.approve(&user1, &user2_id, &BigInt::from_u32(&e, 100));
+    token.approve(&user1, &user3_id, &BigInt::from_u32(&e, 200));
+
+    let approvals = token.allowances(&user1_id, 0, 10);
+    assert_eq!(approvals.len(), 2);
+    assert_eq!(
+        approvals.get_unchecked(0).unwrap(),
+        Approval {
+            spender: user2_id.clone(),
+            amount: BigInt::from_u32(&e, 100),
+        }
+    );
+    assert_eq!(
+        approvals.get_unchecked(1).unwrap(),
+        Approval {
+            spender: user3_id.clone(),
+            amount: BigInt::from_u32(&e, 200),
+        }
+    );
+    assert_eq!(token.allowances(&user1_id, 1, 10).len(), 1);
+
+    // Spending an allowance down to zero drops the spender from the index.
+    token.xfer_from(&user2, &user1_id, &user2_id, &BigInt::from_u32(&e, 100));
+    let approvals = token.allowances(&user1_id, 0, 10);
+    assert_eq!(approvals.len(), 1);
+    assert_eq!(approvals.get_unchecked(0).unwrap().spender, user3_id);
+
+    token.approve(&user1, &user2_id, &BigInt::from_u32(&e, 50));
+    token.revoke_all(&user1);
+    assert_eq!(token.allowances(&user1_id, 0, 10).len(), 0);
+    assert_eq!(token.allowance(&user1_id, &user2_id), BigInt::zero(&e));
+    assert_eq!(token.allowance(&user1_id, &user3_id), BigInt::zero(&e));
+    assert_eq!(token.nonce(&user1_id), BigInt::from_u32(&e, 4));
+}
+
+#[test]
+#[should_panic(expected = "insufficient allowance")]
+fn revoke_all_blocks_xfer_from() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.approve(&user1, &user2_id, &BigInt::from_u32(&e, 100));
+    token.revoke_all(&user1);
+    token.xfer_from(&user2, &user1_id, &user2_id, &BigInt::from_u32(&e, 1));
+}
+
+#[test]
+fn freeze_records() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    assert_eq!(token.freeze_rec(&user1_id), None);
+
+    set_ledger(&e, 5, 100);
+    token.freeze_with(&admin1, &user1_id, FreezeMode::Outbound, 3, Some("case-42"));
+    let frozen = FreezeRecord {
+        mode: FreezeMode::Outbound,
+        reason: 3,
+        reference: Some(bytes(&e, "case-42")),
+        ledger: 5,
+        timestamp: 100,
+        admin: admin1_id.clone(),
+    };
+    assert_eq!(token.freeze_rec(&user1_id), Some(frozen.clone()));
+
+    set_ledger(&e, 9, 200);
+    token.unfreeze_with(&admin1, &user1_id, 4, None);
+    let unfrozen = FreezeRecord {
+        mode: FreezeMode::Off,
+        reason: 4,
+        reference: None,
+        ledger: 9,
+        timestamp: 200,
+        admin: admin1_id.clone(),
+    };
+    assert_eq!(token.freeze_rec(&user1_id), Some(unfrozen.clone()));
+
+    let log = token.freeze_log(&user1_id);
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.get_unchecked(0).unwrap(), frozen);
+    assert_eq!(log.get_unchecked(1).unwrap(), unfrozen);
+}
+
+#[test]
+fn freeze_modes() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.mint(&admin1, &user2_id, &BigInt::from_u32(&e, 1000));
+
+    // An outbound freeze still lets the account receive.
+    token.freeze_with(&admin1, &user1_id, FreezeMode::Outbound, 1, None);
+    assert_eq!(token.is_frozen(&user1_id), FreezeMode::Outbound);
+    token.xfer(&user2, &user1_id, &BigInt::from_u32(&e, 100));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1100));
+
+    // An inbound freeze still lets the account spend.
+    token.freeze_with(&admin1, &user2_id, FreezeMode::Inbound, 1, None);
+    assert_eq!(token.is_frozen(&user2_id), FreezeMode::Inbound);
+    token.xfer(&user2, &admin1_id, &BigInt::from_u32(&e, 100));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 800));
+
+    token.unfreeze(&admin1, &user1_id);
+    assert_eq!(token.is_frozen(&user1_id), FreezeMode::Off);
+    token.xfer(&user1, &admin1_id, &BigInt::from_u32(&e, 100));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1000));
+}
+
+#[test]
+#[should_panic(expected = "can't spend when frozen")]
+fn freeze_outbound_blocks_spend() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.freeze_with(&admin1, &user1_id, FreezeMode::Outbound, 1, None);
+    token.xfer(&user1, &admin1_id, &BigInt::from_u32(&e, 1));
+}
+
+#[test]
+#[should_panic(expected = "can't receive when frozen")]
+fn freeze_inbound_blocks_receive() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.freeze_with(&admin1, &user1_id, FreezeMode::Inbound, 1, None);
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+}
+
+#[test]
+fn max_balance() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let treasury = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+    let treasury_id = to_ed25519(&e, &treasury);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    assert_eq!(token.max_bal(&user1_id), None);
+
+    token.set_max(&admin1, Some(&BigInt::from_u32(&e, 1000)));
+    token.set_max_id(&admin1, &user2_id, Some(&BigInt::from_u32(&e, 2000)));
+    token.set_max_ex(&admin1, &treasury_id, true);
+    assert_eq!(token.max_bal(&user1_id), Some(BigInt::from_u32(&e, 1000)));
+    assert_eq!(token.max_bal(&user2_id), Some(BigInt::from_u32(&e, 2000)));
+    assert_eq!(token.max_bal(&treasury_id), None);
+
+    token.mint(&admin1, &treasury_id, &BigInt::from_u32(&e, 5000));
+    token.xfer(&treasury, &user1_id, &BigInt::from_u32(&e, 1000));
+    token.xfer(&treasury, &user2_id, &BigInt::from_u32(&e, 2000));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1000));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 2000));
+
+    // Removing the override falls back to the global maximum for new credits.
+    token.set_max_id(&admin1, &user2_id, None);
+    assert_eq!(token.max_bal(&user2_id), Some(BigInt::from_u32(&e, 1000)));
+}
+
+#[test]
+#[should_panic(expected = "balance exceeds maximum")]
+fn max_balance_exceeded() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.set_max(&admin1, Some(&BigInt::from_u32(&e, 1000)));
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 600));
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 401));
+}
+
+#[test]
+fn velocity_limit() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    set_ledger(&e, 1, 1000);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 10000));
+    assert_eq!(token.remaining(&user1_id), None);
+
+    let limit = VelocityLimit {
+        amount: BigInt::from_u32(&e, 1000),
+        window: 86400,
+    };
+    token.set_limit(&admin1, &user1_id, Some(limit));
+    assert_eq!(token.remaining(&user1_id), Some(BigInt::from_u32(&e, 1000)));
+
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 600));
+    set_ledger(&e, 2, 1000 + 3600);
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 400));
+    assert_eq!(token.remaining(&user1_id), Some(BigInt::zero(&e)));
+
+    // The first transfer leaves the window a day after it was made.
+    set_ledger(&e, 3, 1000 + 86400);
+    assert_eq!(token.remaining(&user1_id), Some(BigInt::from_u32(&e, 600)));
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 600));
+    assert_eq!(token.balance(&user2_id), BigInt::from_u32(&e, 1600));
+}
+
+#[test]
+#[should_panic(expected = "velocity limit exceeded")]
+fn velocity_limit_exceeded() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 10000));
+    let limit = VelocityLimit {
+        amount: BigInt::from_u32(&e, 1000),
+        window: 86400,
+    };
+    token.set_limit(&admin1, &user1_id, Some(limit));
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 600));
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 401));
+}
+
+#[test]
+fn velocity_limit_negative_xfer() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let user2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let user2_id = to_ed25519(&e, &user2);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 10000));
+    token.mint(&admin1, &user2_id, &BigInt::from_u32(&e, 10000));
+    let limit = VelocityLimit {
+        amount: BigInt::from_u32(&e, 1000),
+        window: 86400,
+    };
+    token.set_limit(&admin1, &user1_id, Some(limit));
+    token.xfer(&user1, &user2_id, &BigInt::from_u32(&e, 1000));
+
+    // A negative transfer is rejected and doesn't free up any of the window.
+    let negative = BigInt::zero(&e) - BigInt::from_u32(&e, 1000);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        token.xfer(&user1, &user2_id, &negative);
+    }));
+    assert!(result.is_err());
+    assert_eq!(token.remaining(&user1_id), Some(BigInt::zero(&e)));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 9000));
+}
+
+#[test]
+fn timelock() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin2 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let admin2_id = to_ed25519(&e, &admin2);
+    let user1_id = to_ed25519(&e, &user1);
+
+    set_ledger(&e, 1, 1000);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::from_u32(&e, 100),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+
+    // Small mints stay direct.
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 100));
+
+    let mint = Action::Mint(MintAction {
+        to: user1_id.clone(),
+        amount: BigInt::from_u32(&e, 1000),
+    });
+    assert_eq!(token.queue(&admin1, mint.clone()), 0);
+    assert_eq!(token.queue(&admin1, Action::SetAdmin(admin2_id.clone())), 1);
+    let mut queued = Vec::new(&e);
+    queued.push_back(QueuedAction {
+        id: 0,
+        action: mint,
+        eta: 1000 + 3600,
+    });
+    queued.push_back(QueuedAction {
+        id: 1,
+        action: Action::SetAdmin(admin2_id.clone()),
+        eta: 1000 + 3600,
+    });
+    assert_eq!(token.queued(), queued);
+
+    token.cancel(&admin1, 1);
+    set_ledger(&e, 2, 1000 + 3600);
+    token.execute(&admin1, 0);
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 1100));
+    assert_eq!(token.queued().len(), 0);
+
+    // Removing the timelock must go through the queue as well.
+    token.queue(&admin1, Action::Unlock);
+    set_ledger(&e, 3, 1000 + 7200);
+    token.execute(&admin1, 2);
+    token.set_admin(&admin1, &admin2_id);
+}
+
+#[test]
+fn timelock_rebase() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    set_ledger(&e, 1, 1000);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::zero(&e),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+
+    let id = token.queue(&admin1, Action::Rebase(BigInt::from_u32(&e, 2_000_000_000)));
+    set_ledger(&e, 2, 1000 + 3600);
+    token.execute(&admin1, id);
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 2000));
+}
+
+#[test]
+#[should_panic(expected = "action must be queued")]
+fn timelock_rebase_must_queue() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::zero(&e),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+    token.rebase(&admin1, &BigInt::from_u32(&e, 2_000_000_000));
+}
+
+#[test]
+#[should_panic(expected = "action must be queued")]
+fn timelock_burn_must_queue() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::zero(&e),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+    token.burn(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+}
+
+#[test]
+#[should_panic(expected = "action must be queued")]
+fn timelock_set_comply_must_queue() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::zero(&e),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+    token.set_comply(&admin1, Some(&generate_contract_id()));
+}
+
+#[test]
+fn timelock_burn_and_freeze() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    set_ledger(&e, 1, 1000);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::zero(&e),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+
+    // Freezing stays immediate.
+    token.freeze(&admin1, &user1_id);
+    assert_eq!(token.is_frozen(&user1_id), FreezeMode::Full);
+    token.unfreeze(&admin1, &user1_id);
+
+    let burn = Action::Burn(BurnAction {
+        from: user1_id.clone(),
+        amount: BigInt::from_u32(&e, 400),
+    });
+    assert_eq!(token.queue(&admin1, burn), 0);
+    set_ledger(&e, 2, 1000 + 3600);
+    token.execute(&admin1, 0);
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 600));
+    assert_eq!(token.supply(), BigInt::from_u32(&e, 600));
+}
+
+#[test]
+#[should_panic(expected = "action must be queued")]
+fn timelock_set_oracle_must_queue() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let oracle = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let oracle_id = to_ed25519(&e, &oracle);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::zero(&e),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+    token.set_oracle(&admin1, &oracle_id);
+}
+
+#[test]
+#[should_panic(expected = "action must be queued")]
+fn timelock_set_rate_must_queue() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::zero(&e),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+    token.set_rate(&admin1, &BigInt::from_u32(&e, 1000));
+}
+
+#[test]
+fn timelock_mint_window() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    set_ledger(&e, 1, 1000);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::from_u32(&e, 100),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 60));
+    set_ledger(&e, 2, 1000 + 3599);
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 40));
+
+    // The exemption is available again once the window has passed.
+    set_ledger(&e, 3, 1000 + 3600);
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 100));
+    assert_eq!(token.balance(&user1_id), BigInt::from_u32(&e, 200));
+}
+
+#[test]
+#[should_panic(expected = "action must be queued")]
+fn timelock_mint_window_exceeded() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::from_u32(&e, 100),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 60));
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 41));
+}
+
+#[test]
+#[should_panic(expected = "action must be queued")]
+fn timelock_must_queue() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::from_u32(&e, 100),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+    token.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 101));
+}
+
+#[test]
+#[should_panic(expected = "action not ready")]
+fn timelock_not_ready() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin2 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let admin2_id = to_ed25519(&e, &admin2);
+
+    set_ledger(&e, 1, 1000);
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    let timelock = Timelock {
+        delay: 3600,
+        mint_max: BigInt::zero(&e),
+    };
+    token.set_tlock(&admin1, Some(timelock));
+    let id = token.queue(&admin1, Action::SetAdmin(admin2_id));
+    set_ledger(&e, 2, 1000 + 3599);
+    token.execute(&admin1, id);
+}
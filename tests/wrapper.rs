@@ -0,0 +1,305 @@
+use ed25519_dalek::Keypair;
+use rand::{thread_rng, RngCore};
+use soroban_auth::Identifier;
+use soroban_sdk::{BigInt, BytesN, Env};
+use soroban_token_contract::testutils::{
+    register_test_contract as register_token, to_ed25519, Token,
+};
+
+fn generate_contract_id() -> [u8; 32] {
+    let mut id: [u8; 32] = Default::default();
+    thread_rng().fill_bytes(&mut id);
+    id
+}
+
+fn generate_keypair() -> Keypair {
+    Keypair::generate(&mut thread_rng())
+}
+
+#[test]
+fn deposit_and_withdraw() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+    let wrapper_contract_id = Identifier::Contract(BytesN::from_array(&e, &wrapper_id));
+
+    let admin1 = generate_keypair();
+    let admin2 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let admin2_id = to_ed25519(&e, &admin2);
+    let user1_id = to_ed25519(&e, &user1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin2_id, &underlying_id, "wrapped", "wsymbol");
+    assert_eq!(wrapper.decimals(), 7);
+
+    underlying.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    underlying.approve(&user1, &wrapper_contract_id, &BigInt::from_u32(&e, 600));
+
+    wrapper.deposit(&user1, &BigInt::from_u32(&e, 600));
+    assert_eq!(underlying.balance(&user1_id), BigInt::from_u32(&e, 400));
+    assert_eq!(
+        underlying.balance(&wrapper_contract_id),
+        BigInt::from_u32(&e, 600)
+    );
+    assert_eq!(wrapper.balance(&user1_id), BigInt::from_u32(&e, 600));
+    assert_eq!(wrapper.supply(), BigInt::from_u32(&e, 600));
+    assert_eq!(wrapper.nonce(&user1_id), BigInt::from_u32(&e, 1));
+
+    wrapper.withdraw(&user1, &BigInt::from_u32(&e, 250));
+    assert_eq!(underlying.balance(&user1_id), BigInt::from_u32(&e, 650));
+    assert_eq!(
+        underlying.balance(&wrapper_contract_id),
+        BigInt::from_u32(&e, 350)
+    );
+    assert_eq!(wrapper.balance(&user1_id), BigInt::from_u32(&e, 350));
+    assert_eq!(wrapper.supply(), BigInt::from_u32(&e, 350));
+}
+
+#[test]
+#[should_panic(expected = "insufficient allowance")]
+fn deposit_without_allowance() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+
+    underlying.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    wrapper.deposit(&user1, &BigInt::from_u32(&e, 600));
+}
+
+#[test]
+#[should_panic(expected = "not a wrapped token")]
+fn deposit_not_wrapped() {
+    let e: Env = Default::default();
+    let contract_id = generate_contract_id();
+    register_token(&e, &contract_id);
+    let token = Token::new(&e, &contract_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    token.initialize(&admin1_id, 7, "name", "symbol");
+    token.deposit(&admin1, &BigInt::from_u32(&e, 1));
+}
+
+#[test]
+fn deposit_credits_amount_received() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+    let wrapper_contract_id = Identifier::Contract(BytesN::from_array(&e, &wrapper_id));
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let collector = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let collector_id = to_ed25519(&e, &collector);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+    underlying.set_fee(
+        &admin1,
+        100,
+        &BigInt::zero(&e),
+        &BigInt::from_u32(&e, 1000),
+        &collector_id,
+    );
+
+    underlying.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    underlying.approve(&user1, &wrapper_contract_id, &BigInt::from_u32(&e, 600));
+
+    // The underlying token keeps a 1% fee, so only 594 reaches the wrapper.
+    wrapper.deposit(&user1, &BigInt::from_u32(&e, 600));
+    assert_eq!(
+        underlying.balance(&wrapper_contract_id),
+        BigInt::from_u32(&e, 594)
+    );
+    assert_eq!(wrapper.balance(&user1_id), BigInt::from_u32(&e, 594));
+    assert_eq!(wrapper.supply(), BigInt::from_u32(&e, 594));
+}
+
+#[test]
+#[should_panic(expected = "amount must be positive")]
+fn deposit_zero() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+    wrapper.deposit(&user1, &BigInt::zero(&e));
+}
+
+#[test]
+#[should_panic(expected = "nothing received from the underlying token")]
+fn deposit_nothing_received() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+    let wrapper_contract_id = Identifier::Contract(BytesN::from_array(&e, &wrapper_id));
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let collector = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+    let collector_id = to_ed25519(&e, &collector);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+    underlying.set_fee(
+        &admin1,
+        0,
+        &BigInt::from_u32(&e, 10),
+        &BigInt::from_u32(&e, 10),
+        &collector_id,
+    );
+
+    underlying.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    underlying.approve(&user1, &wrapper_contract_id, &BigInt::from_u32(&e, 5));
+
+    // The whole deposit goes to the underlying token's fee collector.
+    wrapper.deposit(&user1, &BigInt::from_u32(&e, 5));
+}
+
+#[test]
+#[should_panic(expected = "amount must be positive")]
+fn withdraw_negative() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+
+    // A negative withdrawal would pull underlying tokens from the holder
+    // while crediting them wrapped ones.
+    wrapper.withdraw(&user1, &(BigInt::zero(&e) - BigInt::from_u32(&e, 100)));
+}
+
+#[test]
+#[should_panic(expected = "not allowed on a wrapped token")]
+fn wrapped_mint() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+    wrapper.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+}
+
+#[test]
+#[should_panic(expected = "not allowed on a wrapped token")]
+fn wrapped_burn() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+    let wrapper_contract_id = Identifier::Contract(BytesN::from_array(&e, &wrapper_id));
+
+    let admin1 = generate_keypair();
+    let user1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+    let user1_id = to_ed25519(&e, &user1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+    underlying.mint(&admin1, &user1_id, &BigInt::from_u32(&e, 1000));
+    underlying.approve(&user1, &wrapper_contract_id, &BigInt::from_u32(&e, 600));
+    wrapper.deposit(&user1, &BigInt::from_u32(&e, 600));
+
+    wrapper.burn(&admin1, &user1_id, &BigInt::from_u32(&e, 100));
+}
+
+#[test]
+#[should_panic(expected = "not allowed on a wrapped token")]
+fn wrapped_rebase() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+    wrapper.rebase(&admin1, &BigInt::from_u32(&e, 2_000_000_000));
+}
+
+#[test]
+#[should_panic(expected = "not allowed on a wrapped token")]
+fn wrapped_set_rate() {
+    let e: Env = Default::default();
+    let underlying_id = generate_contract_id();
+    register_token(&e, &underlying_id);
+    let underlying = Token::new(&e, &underlying_id);
+    let wrapper_id = generate_contract_id();
+    register_token(&e, &wrapper_id);
+    let wrapper = Token::new(&e, &wrapper_id);
+
+    let admin1 = generate_keypair();
+    let admin1_id = to_ed25519(&e, &admin1);
+
+    underlying.initialize(&admin1_id, 7, "name", "symbol");
+    wrapper.init_wrap(&admin1_id, &underlying_id, "wrapped", "wsymbol");
+    wrapper.set_rate(&admin1, &BigInt::from_u32(&e, 1000));
+}